@@ -21,6 +21,16 @@ main() {
     RUSTFLAGS='--cfg=secp256k1_fuzz' RUSTDOCFLAGS='--cfg=secp256k1_fuzz' $cargo test --locked --all --features="$features"
 
     $cargo test --all --features="rand serde"
+
+    # `k256` needs Rust 1.65, so it is not in the shared feature lists, which also run on the MSRV.
+    if [ "$(rustc_minor_version)" -ge 65 ]; then
+        $cargo test --all --features="k256"
+        $cargo test --all --no-default-features --features="k256"
+    fi
+}
+
+rustc_minor_version() {
+    rustc --version | cut -d' ' -f2 | cut -d'.' -f2
 }
 
 # ShellCheck can't follow non-constant source, `test_vars_script` is correct.
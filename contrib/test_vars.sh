@@ -5,10 +5,10 @@
 # shellcheck disable=SC2034
 
 # Test all these features with "std" enabled.
-FEATURES_WITH_STD="hashes global-context global-context-less-secure lowmemory rand recovery serde"
+FEATURES_WITH_STD="arbitrary hashes global-context global-context-no-std global-context-less-secure cose ethereum jwk jws nostr lowmemory rand recovery serde signature subtle zeroize"
 
 # Test all these features without "std" enabled.
-FEATURES_WITHOUT_STD="hashes global-context global-context-less-secure lowmemory rand recovery serde alloc"
+FEATURES_WITHOUT_STD="arbitrary hashes global-context global-context-no-std critical-section global-context-less-secure cose ethereum jwk jws nostr lowmemory rand recovery serde signature subtle zeroize alloc"
 
 # Run these examples.
 EXAMPLES="sign_verify:hashes,std sign_verify_recovery:hashes,std,recovery generate_keys:rand,std"
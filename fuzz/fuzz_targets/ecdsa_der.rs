@@ -0,0 +1,38 @@
+//! Parses ECDSA signatures with the strict and lax DER parsers and checks that serializing them
+//! again round-trips.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secp256k1::ecdsa::Signature;
+
+fn check_round_trip(sig: Signature) {
+    let der = sig.serialize_der();
+    assert_eq!(Signature::from_der(&der), Ok(sig));
+    assert_eq!(Signature::from_der_lax(&der), Ok(sig));
+    assert_eq!(Signature::from_compact(&sig.serialize_compact()), Ok(sig));
+    assert_eq!(sig.to_string().parse(), Ok(sig));
+
+    let mut normalized = sig;
+    normalized.normalize_s();
+    let mut twice = normalized;
+    twice.normalize_s();
+    assert_eq!(twice, normalized);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let strict = Signature::from_der(data);
+    if let Ok(sig) = strict {
+        // Every strict DER encoding is also a lax one, and a canonical encoding, i.e. one without
+        // an out-of-range scalar, parses to the same signature.
+        let lax = Signature::from_der_lax(data);
+        assert!(lax.is_ok());
+        if sig.serialize_der()[..] == data[..] {
+            assert_eq!(lax, strict);
+        }
+        check_round_trip(sig);
+    }
+    if let Ok(sig) = Signature::from_der_lax(data) {
+        check_round_trip(sig);
+    }
+});
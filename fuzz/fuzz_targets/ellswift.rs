@@ -0,0 +1,19 @@
+//! Decodes ElligatorSwift encodings and checks that encoding the public keys again round-trips.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secp256k1::ellswift::ElligatorSwift;
+use secp256k1::PublicKey;
+
+fuzz_target!(|data: &[u8]| {
+    // Every 64-byte string is a valid encoding.
+    let Ok(bytes) = <[u8; 64]>::try_from(data) else { return };
+    let ellswift = ElligatorSwift::from_array(bytes);
+    assert_eq!(ellswift.to_array(), bytes);
+    assert_eq!(ellswift.to_string().parse::<ElligatorSwift>().map(|es| es.to_array()), Ok(bytes));
+
+    let pk = PublicKey::from_ellswift(ellswift);
+    assert_eq!(PublicKey::from_slice(&pk.serialize()), Ok(pk));
+    assert_eq!(PublicKey::from_ellswift(ElligatorSwift::from_pubkey(pk)), pk);
+});
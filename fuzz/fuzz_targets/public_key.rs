@@ -0,0 +1,22 @@
+//! Parses public keys and checks that serializing them again round-trips.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secp256k1::{PublicKey, XOnlyPublicKey};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(pk) = PublicKey::from_slice(data) {
+        let compressed = pk.serialize();
+        if data.len() == 33 {
+            assert_eq!(compressed[..], data[..]);
+        }
+        assert_eq!(PublicKey::from_slice(&compressed), Ok(pk));
+        assert_eq!(PublicKey::from_slice(&pk.serialize_uncompressed()), Ok(pk));
+        assert_eq!(pk.to_string().parse(), Ok(pk));
+
+        let (xonly, parity) = pk.x_only_public_key();
+        assert_eq!(XOnlyPublicKey::from_slice(&compressed[1..]), Ok(xonly));
+        assert_eq!(PublicKey::from_x_only_public_key(xonly, parity), pk);
+    }
+});
@@ -0,0 +1,43 @@
+//! Deserializes values of every serde-enabled type from both a human-readable and a binary format
+//! and checks that serializing them again round-trips.
+
+#![no_main]
+
+use core::fmt::Debug;
+
+use libfuzzer_sys::fuzz_target;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature};
+use secp256k1::{ecdh, schnorr, Keypair, Parity, PublicKey, SecretKey, XOnlyPublicKey};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn check_round_trip<T>(data: &[u8])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    if let Ok(value) = serde_json::from_slice::<T>(data) {
+        let json = serde_json::to_vec(&value).unwrap();
+        assert_eq!(serde_json::from_slice::<T>(&json).unwrap(), value);
+    }
+    if let Ok(value) = bincode::deserialize::<T>(data) {
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bincode::deserialize::<T>(&bytes).unwrap(), value);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, data)) = data.split_first() else { return };
+    match selector % 11 {
+        0 => check_round_trip::<SecretKey>(data),
+        1 => check_round_trip::<PublicKey>(data),
+        2 => check_round_trip::<XOnlyPublicKey>(data),
+        3 => check_round_trip::<Keypair>(data),
+        4 => check_round_trip::<Parity>(data),
+        5 => check_round_trip::<Signature>(data),
+        6 => check_round_trip::<schnorr::Signature>(data),
+        7 => check_round_trip::<RecoverableSignature>(data),
+        8 => check_round_trip::<RecoveryId>(data),
+        9 => check_round_trip::<ecdh::SharedSecret>(data),
+        _ => check_round_trip::<(PublicKey, Signature)>(data),
+    }
+});
@@ -0,0 +1,20 @@
+//! Parses x-only public keys and checks that serializing them again round-trips.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use secp256k1::{Parity, XOnlyPublicKey};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(xonly) = XOnlyPublicKey::from_slice(data) {
+        let bytes = xonly.serialize();
+        assert_eq!(bytes[..], data[..]);
+        assert_eq!(XOnlyPublicKey::from_slice(&bytes), Ok(xonly));
+        assert_eq!(xonly.to_string().parse(), Ok(xonly));
+
+        for parity in [Parity::Even, Parity::Odd] {
+            let pk = xonly.public_key(parity);
+            assert_eq!(pk.x_only_public_key(), (xonly, parity));
+        }
+    }
+});
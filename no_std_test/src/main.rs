@@ -115,6 +115,11 @@ fn start(_argc: isize, _argv: *const *const u8) -> isize {
     let _ = SharedSecret::new(&public_key, &secret_key);
     let _ = ecdh::shared_secret_point(&public_key, &secret_key);
 
+    assert!(global::set_entropy_hook(|| [0x55; 32]));
+    assert_eq!(PublicKey::from_secret_key_global(&secret_key), public_key);
+    let sig = secret_key.sign_ecdsa(message);
+    assert!(sig.verify(message, &public_key).is_ok());
+
     #[cfg(feature = "alloc")]
     {
         let secp_alloc = Secp256k1::new();
@@ -96,6 +96,15 @@ pub type EllswiftEcdhHashFn = Option<unsafe extern "C" fn(
     data: *mut c_void,
 ) -> c_int>;
 
+/// A callback function to be called when an illegal argument is passed to an API call or an
+/// internal consistency check fails.
+///
+/// See [`secp256k1_context_set_illegal_callback`] and [`secp256k1_context_set_error_callback`].
+pub type CallbackFn = Option<unsafe extern "C" fn(
+    message: *const c_char,
+    data: *mut c_void,
+)>;
+
 /// Data structure that contains additional arguments for schnorrsig_sign_custom.
 #[repr(C)]
 pub struct SchnorrSigExtraParams {
@@ -582,6 +591,23 @@ extern "C" {
     #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_context_preallocated_destroy")]
     pub fn secp256k1_context_preallocated_destroy(cx: NonNull<Context>);
 
+    #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_context_set_illegal_callback")]
+    pub fn secp256k1_context_set_illegal_callback(cx: NonNull<Context>,
+                                                  fun: CallbackFn,
+                                                  data: *const c_void);
+
+    #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_context_set_error_callback")]
+    pub fn secp256k1_context_set_error_callback(cx: NonNull<Context>,
+                                                fun: CallbackFn,
+                                                data: *const c_void);
+
+    /// Performs basic self tests of the library, calling the default error callback on failure.
+    ///
+    /// This only checks for platform-level problems (e.g. wrong endianness) and is not a
+    /// substitute for known-answer tests of the cryptographic operations.
+    #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_selftest")]
+    pub fn secp256k1_selftest();
+
     // Signatures
     #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_ecdsa_signature_parse_der")]
     pub fn secp256k1_ecdsa_signature_parse_der(cx: *const Context, sig: *mut Signature,
@@ -644,6 +670,10 @@ extern "C" {
                                  output_pubkey: *mut PublicKey,
                                  keypair: *const Keypair)
                                  -> c_int;
+}
+
+#[cfg(not(secp256k1_fuzz))]
+extern "C" {
     // Elligator Swift
     #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_ellswift_encode")]
     pub fn secp256k1_ellswift_encode(ctx: *const Context,
@@ -672,10 +702,7 @@ extern "C" {
                                   hashfp: EllswiftEcdhHashFn,
                                   data: *mut c_void)
                                   -> c_int;
-}
 
-#[cfg(not(secp256k1_fuzz))]
-extern "C" {
     // Contexts
     #[cfg_attr(not(rust_secp_no_symbol_renaming), link_name = "rustsecp256k1_v0_11_context_preallocated_size")]
     pub fn secp256k1_context_preallocated_size(flags: c_uint) -> size_t;
@@ -1194,16 +1221,16 @@ mod fuzz_dummy {
     unsafe fn test_pk_validate(cx: *const Context,
                                pk: *const PublicKey) -> c_int {
         check_context_flags(cx, 0);
-        if (*pk).0[1..32] != (*pk).0[33..64] ||
+        if (&(*pk).0)[1..32] != (&(*pk).0)[33..64] ||
            ((*pk).0[32] != 0 && (*pk).0[32] != 0xff) ||
-           secp256k1_ec_seckey_verify(cx, (*pk).0[0..32].as_ptr()) == 0 {
+           secp256k1_ec_seckey_verify(cx, (&(*pk).0)[0..32].as_ptr()) == 0 {
             0
         } else {
             1
         }
     }
     unsafe fn test_cleanup_pk(pk: *mut PublicKey) {
-        (*pk).0[32..].copy_from_slice(&(*pk).0[..32]);
+        (&mut (*pk).0)[32..].copy_from_slice(&(&(*pk).0)[..32]);
         if (*pk).0[32] <= 0x7f {
             (*pk).0[32] = 0;
         } else {
@@ -1221,8 +1248,8 @@ mod fuzz_dummy {
                 if *input != 2 && *input != 3 {
                     0
                 } else {
-                    ptr::copy(input.offset(1), (*pk).0[0..32].as_mut_ptr(), 32);
-                    ptr::copy(input.offset(2), (*pk).0[33..64].as_mut_ptr(), 31);
+                    ptr::copy(input.offset(1), (&mut (*pk).0)[0..32].as_mut_ptr(), 32);
+                    ptr::copy(input.offset(2), (&mut (*pk).0)[33..64].as_mut_ptr(), 31);
                     if *input == 3 {
                         (*pk).0[32] = 0xff;
                     } else {
@@ -1236,7 +1263,10 @@ mod fuzz_dummy {
                     0
                 } else {
                     ptr::copy(input.offset(1), (*pk).0.as_mut_ptr(), 64);
+                    // Take the parity from the last bit of y, as serialization writes it there.
+                    let y_odd = (*pk).0[63] & 1 == 1;
                     test_cleanup_pk(pk);
+                    (*pk).0[32] = if y_odd { 0xff } else { 0 };
                     test_pk_validate(cx, pk)
                 }
             },
@@ -1263,6 +1293,7 @@ mod fuzz_dummy {
             assert_eq!(*out_len, 65);
             *output = 4;
             ptr::copy((*pk).0.as_ptr(), output.offset(1), 64);
+            *output.offset(64) = ((*pk).0[63] & !1) | ((*pk).0[32] & 1);
         } else {
             panic!("Bad flags");
         }
@@ -1275,7 +1306,7 @@ mod fuzz_dummy {
                                              sk: *const c_uchar) -> c_int {
         check_context_flags(cx, SECP256K1_START_SIGN);
         if secp256k1_ec_seckey_verify(cx, sk) != 1 { return 0; }
-        ptr::copy(sk, (*pk).0[0..32].as_mut_ptr(), 32);
+        ptr::copy(sk, (&mut (*pk).0)[0..32].as_mut_ptr(), 32);
         test_cleanup_pk(pk);
         assert_eq!(test_pk_validate(cx, pk), 1);
         1
@@ -1285,7 +1316,7 @@ mod fuzz_dummy {
                                              pk: *mut PublicKey) -> c_int {
         check_context_flags(cx, 0);
         assert_eq!(test_pk_validate(cx, pk), 1);
-        if secp256k1_ec_seckey_negate(cx, (*pk).0[..32].as_mut_ptr()) != 1 { return 0; }
+        if secp256k1_ec_seckey_negate(cx, (&mut (*pk).0)[..32].as_mut_ptr()) != 1 { return 0; }
         test_cleanup_pk(pk);
         assert_eq!(test_pk_validate(cx, pk), 1);
         1
@@ -1298,7 +1329,7 @@ mod fuzz_dummy {
                                                 -> c_int {
         check_context_flags(cx, SECP256K1_START_VERIFY);
         assert_eq!(test_pk_validate(cx, pk), 1);
-        if secp256k1_ec_seckey_tweak_add(cx, (*pk).0[..32].as_mut_ptr(), tweak) != 1 { return 0; }
+        if secp256k1_ec_seckey_tweak_add(cx, (&mut (*pk).0)[..32].as_mut_ptr(), tweak) != 1 { return 0; }
         test_cleanup_pk(pk);
         assert_eq!(test_pk_validate(cx, pk), 1);
         1
@@ -1311,7 +1342,7 @@ mod fuzz_dummy {
                                                 -> c_int {
         check_context_flags(cx, 0);
         assert_eq!(test_pk_validate(cx, pk), 1);
-        if secp256k1_ec_seckey_tweak_mul(cx, (*pk).0[..32].as_mut_ptr(), tweak) != 1 { return 0; }
+        if secp256k1_ec_seckey_tweak_mul(cx, (&mut (*pk).0)[..32].as_mut_ptr(), tweak) != 1 { return 0; }
         test_cleanup_pk(pk);
         assert_eq!(test_pk_validate(cx, pk), 1);
         1
@@ -1327,7 +1358,7 @@ mod fuzz_dummy {
         (*out) = **ins;
         for i in 1..n {
             assert_eq!(test_pk_validate(cx, *ins.offset(i as isize)), 1);
-            if secp256k1_ec_seckey_tweak_add(cx, (*out).0[..32].as_mut_ptr(), (**ins.offset(i as isize)).0[..32].as_ptr()) != 1 {
+            if secp256k1_ec_seckey_tweak_add(cx, (&mut (*out).0)[..32].as_mut_ptr(), (&(**ins.offset(i as isize)).0)[..32].as_ptr()) != 1 {
                 return 0;
             }
         }
@@ -1350,7 +1381,7 @@ mod fuzz_dummy {
         if secp256k1_ec_seckey_verify(cx, scalar) != 1 { return 0; }
 
         let scalar_slice = slice::from_raw_parts(scalar, 32);
-        let pk_slice = &(*point).0[..32];
+        let pk_slice = &(&(*point).0)[..32];
 
         let mut res_arr = [0u8; 32];
         for i in 0..32 {
@@ -1367,6 +1398,96 @@ mod fuzz_dummy {
         1
     }
 
+    // Elligator Swift
+    /// Sets ell64 to u||t where u is rnd32 with its last bit set to the parity of pk and
+    /// t is u^pk[..32]
+    pub unsafe fn secp256k1_ellswift_encode(
+        cx: *const Context,
+        ell64: *mut c_uchar,
+        pubkey: *const PublicKey,
+        rnd32: *const c_uchar,
+    ) -> c_int {
+        check_context_flags(cx, 0);
+        assert_eq!(test_pk_validate(cx, pubkey), 1);
+        let ell_sl = slice::from_raw_parts_mut(ell64, 64);
+        ell_sl[..32].copy_from_slice(slice::from_raw_parts(rnd32, 32));
+        ell_sl[31] = (ell_sl[31] & !1) | ((*pubkey).0[32] & 1);
+        for i in 0..32 {
+            ell_sl[32 + i] = ell_sl[i] ^ (*pubkey).0[i];
+        }
+        1
+    }
+
+    /// Sets pk to the key with x-coordinate u^t and the parity in the last bit of u, tweaking
+    /// x until it is valid so that every encoding decodes
+    pub unsafe fn secp256k1_ellswift_decode(
+        cx: *const Context,
+        pubkey: *mut u8,
+        ell64: *const c_uchar,
+    ) -> c_int {
+        check_context_flags(cx, 0);
+        let ell_sl = slice::from_raw_parts(ell64, 64);
+        let mut pk_ser = [0u8; 33];
+        pk_ser[0] = 2 + (ell_sl[31] & 1);
+        for i in 0..32 {
+            pk_ser[1 + i] = ell_sl[i] ^ ell_sl[32 + i];
+        }
+        let pk = pubkey as *mut PublicKey;
+        let mut idx = 0;
+        while secp256k1_ec_pubkey_parse(cx, pk, pk_ser.as_ptr(), 33) == 0 {
+            pk_ser[1 + idx / 8] ^= 1 << (idx % 8);
+            idx += 1;
+        }
+        1
+    }
+
+    /// Encodes the public key of seckey32 with aux_rand32 (or zeros) as the randomness
+    pub unsafe fn secp256k1_ellswift_create(
+        cx: *const Context,
+        ell64: *mut c_uchar,
+        seckey32: *const c_uchar,
+        aux_rand32: *const c_uchar,
+    ) -> c_int {
+        check_context_flags(cx, SECP256K1_START_SIGN);
+        let mut pk = PublicKey::new();
+        if secp256k1_ec_pubkey_create(cx, &mut pk, seckey32) != 1 {
+            return 0;
+        }
+        let rnd = if aux_rand32.is_null() { [0u8; 32].as_ptr() } else { aux_rand32 };
+        secp256k1_ellswift_encode(cx, ell64, &pk, rnd)
+    }
+
+    /// Hashes seckey32^pk[..32]^1s, like `secp256k1_ecdh`, where pk is the key of the other
+    /// party
+    pub unsafe fn secp256k1_ellswift_xdh(
+        cx: *const Context,
+        output: *mut c_uchar,
+        ell_a64: *const c_uchar,
+        ell_b64: *const c_uchar,
+        seckey32: *const c_uchar,
+        party: c_int,
+        hashfp: EllswiftEcdhHashFn,
+        data: *mut c_void,
+    ) -> c_int {
+        check_context_flags(cx, 0);
+        if secp256k1_ec_seckey_verify(cx, seckey32) != 1 { return 0; }
+        let hashfn = match hashfp {
+            Some(hashfn) => hashfn,
+            None => return 0,
+        };
+
+        let mut theirs = PublicKey::new();
+        let ell_theirs = if party == 0 { ell_b64 } else { ell_a64 };
+        secp256k1_ellswift_decode(cx, theirs.as_mut_c_ptr() as *mut u8, ell_theirs);
+
+        let seckey_slice = slice::from_raw_parts(seckey32, 32);
+        let mut x32 = [0u8; 32];
+        for i in 0..32 {
+            x32[i] = seckey_slice[i] ^ theirs.0[i] ^ 1;
+        }
+        hashfn(output, x32.as_ptr(), ell_a64, ell_b64, data)
+    }
+
     // ECDSA
     /// Verifies that sig is msg32||pk[..32]
     pub unsafe fn secp256k1_ecdsa_verify(cx: *const Context,
@@ -1378,7 +1499,7 @@ mod fuzz_dummy {
         // Actually verify
         let sig_sl = slice::from_raw_parts(sig as *const u8, 64);
         let msg_sl = slice::from_raw_parts(msg32 as *const u8, 32);
-        if &sig_sl[..32] == msg_sl && sig_sl[32..] == (*pk).0[0..32] {
+        if &sig_sl[..32] == msg_sl && sig_sl[32..] == (&(*pk).0)[0..32] {
             1
         } else {
             0
@@ -1423,7 +1544,7 @@ mod fuzz_dummy {
         // Actually verify
         let sig_sl = slice::from_raw_parts(sig64 as *const u8, 64);
         let msg_sl = slice::from_raw_parts(msg32 as *const u8, msglen);
-        if &sig_sl[..32] == msg_sl && sig_sl[32..] == (*pubkey).0[..32] {
+        if &sig_sl[..32] == msg_sl && sig_sl[32..] == (&(*pubkey).0)[..32] {
             1
         } else {
             0
@@ -1479,8 +1600,8 @@ mod fuzz_dummy {
         if secp256k1_ec_pubkey_create(cx, &mut pk, seckey) == 0 { return 0; }
 
         let seckey_slice = slice::from_raw_parts(seckey, 32);
-        (*keypair).0[..32].copy_from_slice(seckey_slice);
-        (*keypair).0[32..].copy_from_slice(&pk.0);
+        (&mut (*keypair).0)[..32].copy_from_slice(seckey_slice);
+        (&mut (*keypair).0)[32..].copy_from_slice(&pk.0);
         1
     }
 
@@ -1491,8 +1612,8 @@ mod fuzz_dummy {
     ) -> c_int {
         check_context_flags(cx, 0);
         let inslice = slice::from_raw_parts(input32, 32);
-        (*pubkey).0[..32].copy_from_slice(inslice);
-        (*pubkey).0[32..].copy_from_slice(inslice);
+        (&mut (*pubkey).0)[..32].copy_from_slice(inslice);
+        (&mut (*pubkey).0)[32..].copy_from_slice(inslice);
         test_cleanup_pk(pubkey as *mut PublicKey);
         test_pk_validate(cx, pubkey as *mut PublicKey)
     }
@@ -1504,7 +1625,7 @@ mod fuzz_dummy {
     ) -> c_int {
         check_context_flags(cx, 0);
         let outslice = slice::from_raw_parts_mut(output32, 32);
-        outslice.copy_from_slice(&(*pubkey).0[..32]);
+        outslice.copy_from_slice(&(&(*pubkey).0)[..32]);
         1
     }
 
@@ -1516,9 +1637,10 @@ mod fuzz_dummy {
     ) -> c_int {
         check_context_flags(cx, 0);
         if !pk_parity.is_null() {
-            *pk_parity = ((*pubkey).0[32] == 0).into();
+            *pk_parity = ((*pubkey).0[32] != 0).into();
         }
         (*xonly_pubkey).0.copy_from_slice(&(*pubkey).0);
+        test_cleanup_pk(xonly_pubkey as *mut PublicKey);
         assert_eq!(test_pk_validate(cx, pubkey), 1);
         1
     }
@@ -1542,9 +1664,10 @@ mod fuzz_dummy {
     ) -> c_int {
         check_context_flags(cx, 0);
         if !pk_parity.is_null() {
-            *pk_parity = ((*keypair).0[64] == 0).into();
+            *pk_parity = ((*keypair).0[64] != 0).into();
         }
-        (*pubkey).0.copy_from_slice(&(*keypair).0[32..]);
+        (*pubkey).0.copy_from_slice(&(&(*keypair).0)[32..]);
+        test_cleanup_pk(pubkey as *mut PublicKey);
         1
     }
 
@@ -1555,13 +1678,13 @@ mod fuzz_dummy {
     ) -> c_int {
         check_context_flags(cx, SECP256K1_START_VERIFY);
         let mut pk = PublicKey::new();
-        pk.0.copy_from_slice(&(*keypair).0[32..]);
+        pk.0.copy_from_slice(&(&(*keypair).0)[32..]);
         let mut sk = [0u8; 32];
-        sk.copy_from_slice(&(*keypair).0[..32]);
+        sk.copy_from_slice(&(&(*keypair).0)[..32]);
         assert_eq!(secp256k1_ec_pubkey_tweak_add(cx, &mut pk, tweak32), 1);
         assert_eq!(secp256k1_ec_seckey_tweak_add(cx, (&mut sk[..]).as_mut_ptr(), tweak32), 1);
-        (*keypair).0[..32].copy_from_slice(&sk);
-        (*keypair).0[32..].copy_from_slice(&pk.0);
+        (&mut (*keypair).0)[..32].copy_from_slice(&sk);
+        (&mut (*keypair).0)[32..].copy_from_slice(&pk.0);
         1
     }
 
@@ -1576,7 +1699,7 @@ mod fuzz_dummy {
         let mut tweaked_pk = PublicKey::new();
         assert_eq!(secp256k1_xonly_pubkey_tweak_add(cx, &mut tweaked_pk, internal_pubkey, tweak32), 1);
         let in_slice = slice::from_raw_parts(tweaked_pubkey32, 32);
-        if &tweaked_pk.0[..32] == in_slice && tweaked_pubkey_parity == (tweaked_pk.0[32] == 0).into() {
+        if &tweaked_pk.0[..32] == in_slice && tweaked_pubkey_parity == (tweaked_pk.0[32] != 0).into() {
             1
         } else {
             0
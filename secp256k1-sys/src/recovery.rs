@@ -143,7 +143,8 @@ mod fuzz_dummy {
     use crate::{secp256k1_ec_pubkey_create, secp256k1_ec_pubkey_parse, secp256k1_ec_pubkey_serialize, SECP256K1_SER_COMPRESSED};
     use super::*;
 
-    /// Sets sig to msg32||full pk
+    /// Sets sig to msg32||pk[..32]||parity, which converts to the signature made by
+    /// `secp256k1_ecdsa_sign`
     pub unsafe fn secp256k1_ecdsa_sign_recoverable(
         cx: *const Context,
         sig: *mut RecoverableSignature,
@@ -160,17 +161,20 @@ mod fuzz_dummy {
         // Sign
         let sig_sl = slice::from_raw_parts_mut(sig as *mut u8, 65);
         let msg_sl = slice::from_raw_parts(msg32 as *const u8, 32);
-        sig_sl[..32].copy_from_slice(msg_sl);
+        let mut pk_ser = [0u8; 33];
         let mut out_len: size_t = 33;
-        secp256k1_ec_pubkey_serialize(cx, sig_sl[32..].as_mut_ptr(), &mut out_len, &new_pk, SECP256K1_SER_COMPRESSED);
+        secp256k1_ec_pubkey_serialize(cx, pk_ser.as_mut_ptr(), &mut out_len, &new_pk, SECP256K1_SER_COMPRESSED);
+        sig_sl[..32].copy_from_slice(msg_sl);
+        sig_sl[32..64].copy_from_slice(&pk_ser[1..]);
         // Encode the parity of the pubkey in the final byte as 0/1,
         // which is the same encoding (though the parity is computed
         // differently) as real recoverable signatures.
-        sig_sl.swap(32, 64);
-        sig_sl[64] -= 2;
+        sig_sl[64] = pk_ser[0] - 2;
         1
     }
 
+    /// Recovers the pk of a signature made by `secp256k1_ecdsa_sign_recoverable` for msg32,
+    /// or some other pk for a different message
     pub unsafe fn secp256k1_ecdsa_recover(
         cx: *const Context,
         pk: *mut PublicKey,
@@ -183,11 +187,10 @@ mod fuzz_dummy {
         if sig_sl[64] >= 4 {
             return 0;
         }
-        // Pull the original pk out of the siganture
+        // Pull the original pk out of the signature
         let mut pk_ser = [0u8; 33];
-        pk_ser.copy_from_slice(&sig_sl[32..]);
-        pk_ser.swap(0, 32);
-        pk_ser[0] += 2;
+        pk_ser[0] = 2 + (sig_sl[64] & 1);
+        pk_ser[1..].copy_from_slice(&sig_sl[32..64]);
         // Check that it parses (in a real sig, this would be the R value,
         // so it is actually required to be a valid point)
         if secp256k1_ec_pubkey_parse(cx, pk, pk_ser.as_ptr(), 33) == 0 {
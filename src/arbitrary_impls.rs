@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Implements [`Arbitrary`] for keys, signatures and messages.
+//!
+//! Every generated value is valid, so structure-aware fuzzers never waste their input on values
+//! that would fail to parse. Public keys and keypairs are derived from arbitrary secret keys, and
+//! signatures are created by signing arbitrary messages, using the global [`SECP256K1`] context.
+//!
+
+use arbitrary::{size_hint, Arbitrary, Result, Unstructured};
+
+#[cfg(feature = "recovery")]
+use crate::ecdsa::RecoverableSignature;
+use crate::ellswift::ElligatorSwift;
+use crate::{
+    constants, ecdsa, schnorr, Keypair, Message, Parity, PublicKey, Scalar, SecretKey,
+    XOnlyPublicKey, SECP256K1,
+};
+
+/// Maps byte strings that are not valid secret keys, such as the zeros of an exhausted input, to
+/// the secret key one.
+impl<'a> Arbitrary<'a> for SecretKey {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bytes = <[u8; constants::SECRET_KEY_SIZE]>::arbitrary(u)?;
+        Ok(SecretKey::from_byte_array(&bytes)
+            .unwrap_or_else(|_| SecretKey::from_byte_array(&constants::ONE).unwrap()))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; constants::SECRET_KEY_SIZE]>::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for PublicKey {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(SecretKey::arbitrary(u)?.public_key(SECP256K1))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) { SecretKey::size_hint(depth) }
+}
+
+impl<'a> Arbitrary<'a> for XOnlyPublicKey {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(PublicKey::arbitrary(u)?.x_only_public_key().0)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) { PublicKey::size_hint(depth) }
+}
+
+impl<'a> Arbitrary<'a> for Keypair {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Keypair::from_secret_key(SECP256K1, &SecretKey::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) { SecretKey::size_hint(depth) }
+}
+
+impl<'a> Arbitrary<'a> for Parity {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if bool::arbitrary(u)? { Parity::Odd } else { Parity::Even })
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) { bool::size_hint(depth) }
+}
+
+/// Maps byte strings that are not below the curve order to [`Scalar::MAX`].
+impl<'a> Arbitrary<'a> for Scalar {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Scalar::from_be_bytes(<[u8; 32]>::arbitrary(u)?).unwrap_or(Scalar::MAX))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) { <[u8; 32]>::size_hint(depth) }
+}
+
+impl<'a> Arbitrary<'a> for Message {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Message::from_digest(<[u8; constants::MESSAGE_SIZE]>::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; constants::MESSAGE_SIZE]>::size_hint(depth)
+    }
+}
+
+/// Signs an arbitrary message with an arbitrary secret key.
+impl<'a> Arbitrary<'a> for ecdsa::Signature {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let sk = SecretKey::arbitrary(u)?;
+        Ok(SECP256K1.sign_ecdsa(Message::arbitrary(u)?, &sk))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(SecretKey::size_hint(depth), Message::size_hint(depth))
+    }
+}
+
+/// Signs an arbitrary message with an arbitrary secret key.
+#[cfg(feature = "recovery")]
+impl<'a> Arbitrary<'a> for RecoverableSignature {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let sk = SecretKey::arbitrary(u)?;
+        Ok(SECP256K1.sign_ecdsa_recoverable(Message::arbitrary(u)?, &sk))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(SecretKey::size_hint(depth), Message::size_hint(depth))
+    }
+}
+
+/// Signs an arbitrary 32-byte message with an arbitrary keypair and auxiliary random data.
+impl<'a> Arbitrary<'a> for schnorr::Signature {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let keypair = Keypair::arbitrary(u)?;
+        let msg = <[u8; 32]>::arbitrary(u)?;
+        let aux_rand = <[u8; 32]>::arbitrary(u)?;
+        Ok(SECP256K1.sign_schnorr_with_aux_rand(&msg, &keypair, &aux_rand))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and_all(&[
+            Keypair::size_hint(depth),
+            <[u8; 32]>::size_hint(depth),
+            <[u8; 32]>::size_hint(depth),
+        ])
+    }
+}
+
+/// Every 64-byte string encodes a public key, so this uses the arbitrary bytes as they are.
+impl<'a> Arbitrary<'a> for ElligatorSwift {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(ElligatorSwift::from_array(<[u8; 64]>::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) { <[u8; 64]>::size_hint(depth) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_input() {
+        let mut u = Unstructured::new(&[]);
+        let sk = SecretKey::arbitrary(&mut u).unwrap();
+        assert_eq!(sk.secret_bytes(), constants::ONE);
+        assert_eq!(Scalar::arbitrary(&mut u).unwrap(), Scalar::ZERO);
+        assert_eq!(Parity::arbitrary(&mut u).unwrap(), Parity::Even);
+
+        let mut u = Unstructured::new(&[0xff; 32]);
+        assert_eq!(SecretKey::arbitrary(&mut u).unwrap(), sk);
+        let mut u = Unstructured::new(&[0xff; 32]);
+        assert_eq!(Scalar::arbitrary(&mut u).unwrap(), Scalar::MAX);
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn valid_values() {
+        let data: Vec<u8> = (0..=255).cycle().take(1024).collect();
+        let mut u = Unstructured::new(&data);
+
+        let sk = SecretKey::arbitrary(&mut u).unwrap();
+        let pk = PublicKey::arbitrary(&mut u).unwrap();
+        assert_eq!(PublicKey::from_slice(&pk.serialize()), Ok(pk));
+        let keypair = Keypair::arbitrary(&mut u).unwrap();
+        assert_eq!(keypair.public_key(), keypair.secret_key().public_key(SECP256K1));
+        assert_ne!(keypair.secret_key(), sk);
+
+        let sig = ecdsa::Signature::arbitrary(&mut u).unwrap();
+        let mut normalized = sig;
+        normalized.normalize_s();
+        assert_eq!(normalized, sig);
+        assert_eq!(ecdsa::Signature::from_der(&sig.serialize_der()), Ok(sig));
+
+        let sig = schnorr::Signature::arbitrary(&mut u).unwrap();
+        assert_eq!(schnorr::Signature::from_slice(sig.as_ref()), Ok(sig));
+        assert!(!u.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "recovery", not(secp256k1_fuzz)))]
+    fn recoverable_signature() {
+        let data = [0x42; 64];
+        let mut u = Unstructured::new(&data);
+        let sig = RecoverableSignature::arbitrary(&mut u).unwrap();
+
+        let sk = SecretKey::from_byte_array(&[0x42; 32]).unwrap();
+        let msg = Message::from_digest([0x42; 32]);
+        assert_eq!(SECP256K1.recover_ecdsa(msg, &sig), Ok(sk.public_key(SECP256K1)));
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Minimal base64 encoding and decoding (RFC 4648) for the key encodings in this crate.
+//!
+//! Decoding is strict: the input must be canonical, i.e. padding must be present exactly when
+//! requested and unused trailing bits must be zero.
+//!
+
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+
+/// The base64 alphabet to use.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Alphabet {
+    /// The standard alphabet with `+` and `/`, as used by PEM.
+    Standard,
+    /// The URL- and filename-safe alphabet with `-` and `_`, as used by JOSE.
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn chars(self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard =>
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            Alphabet::UrlSafe =>
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+        }
+    }
+
+    fn value(self, c: u8) -> Option<u8> {
+        match c {
+            b'A'..=b'Z' => Some(c - b'A'),
+            b'a'..=b'z' => Some(c - b'a' + 26),
+            b'0'..=b'9' => Some(c - b'0' + 52),
+            b'+' if self == Alphabet::Standard => Some(62),
+            b'/' if self == Alphabet::Standard => Some(63),
+            b'-' if self == Alphabet::UrlSafe => Some(62),
+            b'_' if self == Alphabet::UrlSafe => Some(63),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes `data`, appending `=` padding if `pad` is set.
+pub(crate) fn encode(data: &[u8], alphabet: Alphabet, pad: bool) -> String {
+    let chars = alphabet.chars();
+    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        let symbols = chunk.len() + 1;
+        for i in 0..4 {
+            if i < symbols {
+                out.push(chars[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else if pad {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes `data`, requiring `=` padding if `pad` is set and rejecting it otherwise.
+pub(crate) fn decode(data: &[u8], alphabet: Alphabet, pad: bool) -> Result<Vec<u8>, ()> {
+    let data = if pad {
+        if data.len() % 4 != 0 {
+            return Err(());
+        }
+        match data {
+            [rest @ .., b'=', b'='] => rest,
+            [rest @ .., b'='] => rest,
+            _ => data,
+        }
+    } else {
+        data
+    };
+    if data.len() % 4 == 1 {
+        return Err(());
+    }
+
+    let mut out = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    for chunk in data.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            n |= u32::from(alphabet.value(*c).ok_or(())?) << (18 - 6 * i);
+        }
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        let len = chunk.len() - 1;
+        // Non-canonical encodings have non-zero bits after the last full byte.
+        if bytes[len..].iter().any(|b| *b != 0) {
+            return Err(());
+        }
+        out.extend_from_slice(&bytes[..len]);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (data, encoded) in vectors {
+            assert_eq!(encode(data.as_bytes(), Alphabet::Standard, true), encoded);
+            assert_eq!(
+                decode(encoded.as_bytes(), Alphabet::Standard, true).unwrap(),
+                data.as_bytes()
+            );
+
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(encode(data.as_bytes(), Alphabet::UrlSafe, false), unpadded);
+            assert_eq!(
+                decode(unpadded.as_bytes(), Alphabet::UrlSafe, false).unwrap(),
+                data.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn alphabets() {
+        assert_eq!(encode(&[0xfb, 0xff], Alphabet::Standard, true), "+/8=");
+        assert_eq!(encode(&[0xfb, 0xff], Alphabet::UrlSafe, false), "-_8");
+        assert!(decode(b"+/8=", Alphabet::UrlSafe, true).is_err());
+        assert!(decode(b"-_8", Alphabet::Standard, false).is_err());
+    }
+
+    #[test]
+    fn strict_decoding() {
+        // Missing, unexpected and misplaced padding.
+        assert!(decode(b"Zg", Alphabet::Standard, true).is_err());
+        assert!(decode(b"Zg==", Alphabet::UrlSafe, false).is_err());
+        assert!(decode(b"Z===", Alphabet::Standard, true).is_err());
+        assert!(decode(b"Zg=a", Alphabet::Standard, true).is_err());
+        // Impossible length.
+        assert!(decode(b"Zm9vY", Alphabet::UrlSafe, false).is_err());
+        // Non-zero trailing bits.
+        assert!(decode(b"Zh==", Alphabet::Standard, true).is_err());
+        assert!(decode(b"Zm9=", Alphabet::Standard, true).is_err());
+        // Whitespace is not skipped.
+        assert!(decode(b"Zm9v Yg==", Alphabet::Standard, true).is_err());
+    }
+}
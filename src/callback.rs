@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Helpers for passing Rust closures to libsecp256k1 as C callbacks.
+//!
+
+use core::{slice, str};
+
+use crate::ffi::types::{c_char, c_void};
+
+/// Keeps panics raised by user callbacks from unwinding into C code.
+///
+/// Unwinding across an `extern "C"` boundary is undefined behavior, so every trampoline runs the
+/// user's closure through [`PanicGuard::call`]. Once control is back on the Rust side the caller
+/// re-raises any caught panic with [`PanicGuard::resume`].
+///
+/// Without the `std` feature panics cannot be caught; `no_std` users are expected to build with
+/// `panic = "abort"`.
+#[derive(Default)]
+pub(crate) struct PanicGuard {
+    #[cfg(feature = "std")]
+    payload: Option<Box<dyn core::any::Any + Send + 'static>>,
+}
+
+impl PanicGuard {
+    /// Runs `f`, returning `on_panic` if it panics (or if an earlier call already did).
+    #[inline]
+    pub(crate) fn call<R>(&mut self, on_panic: R, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "std")]
+        {
+            if self.payload.is_some() {
+                return on_panic;
+            }
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+                Ok(ret) => ret,
+                Err(payload) => {
+                    self.payload = Some(payload);
+                    on_panic
+                }
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = on_panic;
+            f()
+        }
+    }
+
+    /// Re-raises a panic caught by [`PanicGuard::call`], if any.
+    #[inline]
+    pub(crate) fn resume(self) {
+        #[cfg(feature = "std")]
+        if let Some(payload) = self.payload {
+            std::panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Calls the `'static` closure pointed to by `data` on behalf of libsecp256k1's illegal argument
+/// and error callbacks.
+pub(crate) unsafe extern "C" fn message_callback_trampoline<F>(
+    message: *const c_char,
+    data: *mut c_void,
+) where
+    F: Fn(&str) + Sync + 'static,
+{
+    let callback = &*(data as *const F);
+    let mut len = 0;
+    while *message.add(len) != 0 {
+        len += 1;
+    }
+    let bytes = slice::from_raw_parts(message as *const u8, len);
+    let message = str::from_utf8(bytes).unwrap_or("<non-UTF-8 message>");
+
+    #[cfg(feature = "std")]
+    {
+        let call = std::panic::AssertUnwindSafe(|| callback(message));
+        if std::panic::catch_unwind(call).is_err() {
+            // There is nowhere to propagate the panic to; unwinding into C is undefined behavior.
+            std::process::abort();
+        }
+    }
+    #[cfg(not(feature = "std"))]
+    callback(message);
+}
@@ -10,14 +10,21 @@ use crate::ffi::types::{c_uint, c_void, AlignedType};
 use crate::ffi::{self, CPtr};
 use crate::{Error, Secp256k1};
 
-#[cfg(all(feature = "global-context", feature = "std"))]
+#[cfg(feature = "global-context-no-std")]
 /// Module implementing a singleton pattern for a global `Secp256k1` context.
+///
+/// With `std` the context is a `Secp256k1<All>` initialized with [`std::sync::Once`]. Without it
+/// the context is a `Secp256k1<AllPreallocated<'static>>` in a static buffer, initialized on first
+/// use either inside a critical section (with the `critical-section` feature) or by spinning on an
+/// atomic flag, so it is also available on microcontrollers.
 pub mod global {
+    use core::ops::Deref;
+    use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+    use core::{mem, ptr};
 
-    use std::ops::Deref;
-    use std::sync::Once;
-
-    use crate::{All, Secp256k1};
+    #[cfg(not(feature = "std"))]
+    use crate::AllPreallocated;
+    use crate::Secp256k1;
 
     /// Proxy struct for global `SECP256K1` context.
     #[derive(Debug, Copy, Clone)]
@@ -28,7 +35,8 @@ pub mod global {
     /// A global static context to avoid repeatedly creating contexts.
     ///
     /// If `rand` and `std` feature is enabled, context will have been randomized using
-    /// `thread_rng`.
+    /// `thread_rng`. If an entropy hook has been installed with [`set_entropy_hook`] the context is
+    /// additionally randomized with its output.
     ///
     /// ```
     /// # #[cfg(all(feature = "global-context", feature = "rand", feature = "std"))] {
@@ -38,17 +46,158 @@ pub mod global {
     /// ```
     pub static SECP256K1: &GlobalContext = &GlobalContext { __private: () };
 
+    /// The entropy hook installed with [`set_entropy_hook`], or null.
+    static ENTROPY_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+    /// Set once initialization of the global context has started.
+    static STARTED: AtomicBool = AtomicBool::new(false);
+
+    /// Installs a function that provides 32 bytes of entropy for randomizing the global context.
+    ///
+    /// The hook is called once, when the global context is first used. This is the only way to
+    /// randomize the global context in `no_std` builds; in `std` builds with the `rand` feature it
+    /// is applied on top of the `thread_rng` randomization.
+    ///
+    /// Returns `false` if the global context has already been initialized, in which case the hook
+    /// may not be called. Install the hook early during startup, before any `*_global` function
+    /// or [`SECP256K1`] is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "global-context-no-std")] {
+    /// use secp256k1::global;
+    ///
+    /// fn hardware_rng() -> [u8; 32] {
+    ///     // Read from the device's true random number generator here.
+    /// #   [0x42; 32]
+    /// }
+    ///
+    /// global::set_entropy_hook(hardware_rng);
+    /// # }
+    /// ```
+    pub fn set_entropy_hook(hook: fn() -> [u8; 32]) -> bool {
+        ENTROPY_HOOK.store(hook as *mut (), Ordering::SeqCst);
+        !STARTED.load(Ordering::SeqCst)
+    }
+
+    /// Creates the context and randomizes it with the entropy hook, if any.
+    ///
+    /// With `std` this is a [`Secp256k1::new`] context, so it has also been randomized with
+    /// `thread_rng` if `rand` is enabled.
+    #[cfg(feature = "std")]
+    fn create() -> Secp256k1<crate::All> {
+        STARTED.store(true, Ordering::SeqCst);
+        #[allow(unused_mut)] // Unused when there is no entropy hook.
+        let mut ctx = Secp256k1::new();
+        apply_entropy_hook(&mut ctx);
+        ctx
+    }
+
+    /// Creates the context in a static buffer and randomizes it with the entropy hook, if any.
+    #[cfg(not(feature = "std"))]
+    fn create() -> Secp256k1<AllPreallocated<'static>> {
+        use crate::ffi::types::AlignedType;
+
+        /// Comfortably larger than the context, whose size is a few hundred bytes.
+        const BUFFER_WORDS: usize = 64;
+        static mut BUFFER: [AlignedType; BUFFER_WORDS] = [AlignedType::ZERO; BUFFER_WORDS];
+
+        STARTED.store(true, Ordering::SeqCst);
+        // Only called once, or again after a call that panicked and so dropped its context, so
+        // this is the only reference to `BUFFER`.
+        let buf = unsafe { &mut *ptr::addr_of_mut!(BUFFER) };
+        let mut ctx = Secp256k1::preallocated_new(buf).expect("static buffer fits the context");
+        apply_entropy_hook(&mut ctx);
+        ctx
+    }
+
+    /// Randomizes `ctx` with the output of the entropy hook, if one is installed.
+    fn apply_entropy_hook<C: crate::Context>(ctx: &mut Secp256k1<C>) {
+        let hook = ENTROPY_HOOK.load(Ordering::SeqCst);
+        if !hook.is_null() {
+            let hook = unsafe { mem::transmute::<*mut (), fn() -> [u8; 32]>(hook) };
+            ctx.seeded_randomize(&hook());
+        }
+    }
+
     impl Deref for GlobalContext {
-        type Target = Secp256k1<All>;
+        #[cfg(feature = "std")]
+        type Target = Secp256k1<crate::All>;
+        #[cfg(not(feature = "std"))]
+        type Target = Secp256k1<AllPreallocated<'static>>;
 
-        #[allow(unused_mut)] // Unused when `rand` + `std` is not enabled.
+        #[cfg(feature = "std")]
         fn deref(&self) -> &Self::Target {
+            use std::sync::Once;
+
             static ONCE: Once = Once::new();
-            static mut CONTEXT: Option<Secp256k1<All>> = None;
+            static mut CONTEXT: Option<Secp256k1<crate::All>> = None;
             ONCE.call_once(|| unsafe {
-                CONTEXT = Some(Secp256k1::new());
+                *ptr::addr_of_mut!(CONTEXT) = Some(create());
             });
-            unsafe { CONTEXT.as_ref().unwrap() }
+            unsafe { (*ptr::addr_of!(CONTEXT)).as_ref().unwrap() }
+        }
+
+        #[cfg(not(feature = "std"))]
+        fn deref(&self) -> &Self::Target {
+            use core::mem::MaybeUninit;
+
+            static mut CONTEXT: MaybeUninit<Secp256k1<AllPreallocated<'static>>> =
+                MaybeUninit::uninit();
+            static READY: AtomicBool = AtomicBool::new(false);
+
+            #[cold]
+            fn init() {
+                unsafe fn write_context() { (*ptr::addr_of_mut!(CONTEXT)).write(create()); }
+
+                #[cfg(feature = "critical-section")]
+                critical_section::with(|_| {
+                    if !READY.load(Ordering::Acquire) {
+                        unsafe { write_context() };
+                        READY.store(true, Ordering::Release);
+                    }
+                });
+
+                #[cfg(not(feature = "critical-section"))]
+                {
+                    use core::sync::atomic::AtomicU8;
+
+                    const UNINIT: u8 = 0;
+                    const RUNNING: u8 = 1;
+
+                    static STATE: AtomicU8 = AtomicU8::new(UNINIT);
+
+                    /// Lets another caller retry if creating the context panics, for example in
+                    /// the entropy hook, instead of leaving it spinning forever.
+                    struct ResetOnUnwind;
+
+                    impl Drop for ResetOnUnwind {
+                        fn drop(&mut self) { STATE.store(UNINIT, Ordering::Release); }
+                    }
+
+                    loop {
+                        if STATE
+                            .compare_exchange(UNINIT, RUNNING, Ordering::Acquire, Ordering::Acquire)
+                            .is_ok()
+                        {
+                            let reset = ResetOnUnwind;
+                            unsafe { write_context() };
+                            mem::forget(reset);
+                            READY.store(true, Ordering::Release);
+                            return;
+                        }
+                        if READY.load(Ordering::Acquire) {
+                            return;
+                        }
+                        core::hint::spin_loop();
+                    }
+                }
+            }
+
+            if !READY.load(Ordering::Acquire) {
+                init();
+            }
+            unsafe { (*ptr::addr_of!(CONTEXT)).assume_init_ref() }
         }
     }
 }
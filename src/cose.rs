@@ -0,0 +1,685 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides CBOR Object Signing and Encryption ([RFC 9052]) single-signer messages (`COSE_Sign1`)
+//! and keys (`COSE_Key`), using the `ES256K` algorithm and `secp256k1` curve registered by
+//! [RFC 8812].
+//!
+//! An `ES256K` signature is an ECDSA signature over the SHA-256 hash of the CBOR encoded
+//! `Sig_structure`, encoded as the 64-byte `r || s` compact serialization. Signatures with a high
+//! S value are rejected.
+//!
+//! Only the subset of CBOR used by these structures is supported. Decoding requires definite
+//! lengths and the shortest encoding of integers and lengths, and skips unknown header parameters
+//! and key parameters, but rejects header and key maps with more than 64 entries. Detached payloads
+//! are not supported.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "cose", feature = "std", not(secp256k1_fuzz)))] {
+//! use secp256k1::{cose, Secp256k1, SecretKey};
+//!
+//! let secp = Secp256k1::new();
+//! let sk = SecretKey::from_byte_array(&[0xcd; 32]).expect("valid secret key");
+//! let header = cose::Header::new().with_kid(b"key-1");
+//!
+//! let message = cose::sign1(&secp, &header, b"attestation", b"", &sk);
+//! let (header, payload) = cose::verify1(&secp, &message, b"", &sk.public_key(&secp)).unwrap();
+//! assert_eq!(header.kid(), Some(&b"key-1"[..]));
+//! assert_eq!(payload, b"attestation");
+//! # }
+//! ```
+//!
+//! [RFC 9052]: https://datatracker.ietf.org/doc/html/rfc9052
+//! [RFC 8812]: https://datatracker.ietf.org/doc/html/rfc8812
+//!
+
+use core::fmt;
+
+use hashes::{sha256, Hash};
+
+use crate::alloc::vec::Vec;
+use crate::ecdsa::Signature;
+use crate::{constants, Message, PublicKey, Secp256k1, SecretKey, Signing, Verification};
+
+/// The COSE algorithm identifier of `ES256K`.
+pub const ALGORITHM: i64 = -47;
+/// The COSE elliptic curve identifier of `secp256k1`.
+pub const CURVE: i64 = 8;
+/// The CBOR tag of a `COSE_Sign1` message.
+pub const SIGN1_TAG: u64 = 18;
+
+/// The `EC2` COSE key type.
+const KEY_TYPE_EC2: i64 = 2;
+
+/// Common header parameter labels.
+const HEADER_ALG: i64 = 1;
+const HEADER_CRIT: i64 = 2;
+const HEADER_KID: i64 = 4;
+
+/// COSE key parameter labels.
+const KEY_KTY: i64 = 1;
+const KEY_CRV: i64 = -1;
+const KEY_X: i64 = -2;
+const KEY_Y: i64 = -3;
+
+/// Maximum nesting depth of skipped values, to bound recursion on untrusted input.
+const MAX_DEPTH: usize = 32;
+/// Maximum number of entries in a header or key map, to bound the duplicate key check on
+/// untrusted input.
+const MAX_ENTRIES: usize = 64;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_FALSE: u64 = 20;
+const SIMPLE_TRUE: u64 = 21;
+
+/// Error decoding a COSE structure or verifying a COSE signature.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input is not supported CBOR or does not have the expected structure.
+    Malformed,
+    /// The protected header is missing the algorithm or names an algorithm other than `ES256K`.
+    UnsupportedAlgorithm,
+    /// The protected header lists critical parameters, none of which are supported.
+    UnsupportedCriticalHeader,
+    /// The key type is not `EC2`.
+    UnsupportedKeyType,
+    /// The key curve is not `secp256k1`.
+    UnsupportedCurve,
+    /// The key coordinates are not a point on the curve.
+    InvalidPublicKey,
+    /// The signature is not a 64-byte `r || s` encoding.
+    InvalidSignature,
+    /// The signature has a high S value.
+    HighS,
+    /// The signature does not verify.
+    IncorrectSignature,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        let s = match *self {
+            Malformed => "malformed COSE structure",
+            UnsupportedAlgorithm => "COSE algorithm is not ES256K",
+            UnsupportedCriticalHeader => "unsupported critical COSE header parameter",
+            UnsupportedKeyType => "COSE key type is not EC2",
+            UnsupportedCurve => "COSE key curve is not secp256k1",
+            InvalidPublicKey => "invalid COSE public key",
+            InvalidSignature => "invalid ES256K signature encoding",
+            HighS => "ES256K signature has a high S value",
+            IncorrectSignature => "signature failed verification",
+        };
+        f.write_str(s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A map key or other integer or text value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Label<'a> {
+    Int(i64),
+    Text(&'a str),
+}
+
+/// Reads CBOR data items from a byte slice.
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self { Decoder { data } }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::Malformed);
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    /// Reads the initial byte and argument of a data item, requiring the shortest encoding.
+    fn read_head(&mut self) -> Result<(u8, u64), Error> {
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let (arg, min) = match info {
+            0..=23 => return Ok((major, u64::from(info))),
+            // Simple values 24..=31 are reserved, so one-byte simple values start at 32.
+            24 if major == MAJOR_SIMPLE => (u64::from(self.take(1)?[0]), 32),
+            24 => (u64::from(self.take(1)?[0]), 24),
+            // Floating-point numbers are not used by COSE headers or keys.
+            25..=27 if major == MAJOR_SIMPLE => return Err(Error::Malformed),
+            25 =>
+                (u64::from(u16::from_be_bytes(self.take(2)?.try_into().expect("2 bytes"))), 0x100),
+            26 => (
+                u64::from(u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes"))),
+                0x1_0000,
+            ),
+            27 => (u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")), 0x1_0000_0000),
+            // Indefinite lengths and reserved values.
+            _ => return Err(Error::Malformed),
+        };
+        if arg < min {
+            return Err(Error::Malformed);
+        }
+        Ok((major, arg))
+    }
+
+    fn peek_head(&self) -> Result<(u8, u64), Error> { Decoder { data: self.data }.read_head() }
+
+    fn read_len(&mut self, major: u8) -> Result<usize, Error> {
+        match self.read_head()? {
+            (m, len) if m == major => usize::try_from(len).map_err(|_| Error::Malformed),
+            _ => Err(Error::Malformed),
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.read_len(MAJOR_BYTES)?;
+        self.take(len)
+    }
+
+    fn read_array(&mut self) -> Result<usize, Error> { self.read_len(MAJOR_ARRAY) }
+
+    fn read_map(&mut self) -> Result<usize, Error> { self.read_len(MAJOR_MAP) }
+
+    /// Reads an integer or a text string.
+    fn read_label(&mut self) -> Result<Label<'a>, Error> {
+        match self.read_head()? {
+            (MAJOR_UNSIGNED, n) => i64::try_from(n).map(Label::Int).map_err(|_| Error::Malformed),
+            (MAJOR_NEGATIVE, n) =>
+                i64::try_from(n).map(|n| Label::Int(-1 - n)).map_err(|_| Error::Malformed),
+            (MAJOR_TEXT, len) => {
+                let len = usize::try_from(len).map_err(|_| Error::Malformed)?;
+                core::str::from_utf8(self.take(len)?).map(Label::Text).map_err(|_| Error::Malformed)
+            }
+            _ => Err(Error::Malformed),
+        }
+    }
+
+    fn read_int(&mut self) -> Result<i64, Error> {
+        match self.read_label()? {
+            Label::Int(n) => Ok(n),
+            Label::Text(_) => Err(Error::Malformed),
+        }
+    }
+
+    /// Skips a data item of any type.
+    fn skip(&mut self, depth: usize) -> Result<(), Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::Malformed);
+        }
+        match self.read_head()? {
+            (MAJOR_UNSIGNED | MAJOR_NEGATIVE, _) => Ok(()),
+            (MAJOR_BYTES | MAJOR_TEXT, len) => {
+                let len = usize::try_from(len).map_err(|_| Error::Malformed)?;
+                self.take(len).map(|_| ())
+            }
+            (MAJOR_ARRAY, len) => (0..len).try_for_each(|_| self.skip(depth + 1)),
+            (MAJOR_MAP, len) => (0..len.saturating_mul(2)).try_for_each(|_| self.skip(depth + 1)),
+            (MAJOR_TAG, _) => self.skip(depth + 1),
+            _ => Ok(()),
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Malformed)
+        }
+    }
+}
+
+/// Reads a map whose keys are integers or text strings, calling `f` with each key and a decoder
+/// positioned at its value. `f` must consume the value. Duplicate keys and maps with more than
+/// [`MAX_ENTRIES`] entries are rejected.
+fn for_each_entry<'a, F>(decoder: &mut Decoder<'a>, mut f: F) -> Result<(), Error>
+where
+    F: FnMut(Label<'a>, &mut Decoder<'a>) -> Result<(), Error>,
+{
+    let len = decoder.read_map()?;
+    if len > MAX_ENTRIES {
+        return Err(Error::Malformed);
+    }
+    let mut seen = Vec::with_capacity(len);
+    for _ in 0..len {
+        let label = decoder.read_label()?;
+        if seen.contains(&label) {
+            return Err(Error::Malformed);
+        }
+        seen.push(label);
+        f(label, decoder)?;
+    }
+    Ok(())
+}
+
+/// Appends the initial byte and argument of a data item to `out`, using the shortest encoding.
+fn write_head(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if arg <= 0xff {
+        out.extend_from_slice(&[major | 24, arg as u8]);
+    } else if arg <= 0xffff {
+        out.push(major | 25);
+        out.extend_from_slice(&(arg as u16).to_be_bytes());
+    } else if arg <= 0xffff_ffff {
+        out.push(major | 26);
+        out.extend_from_slice(&(arg as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+fn write_int(out: &mut Vec<u8>, n: i64) {
+    if n >= 0 {
+        write_head(out, MAJOR_UNSIGNED, n as u64);
+    } else {
+        write_head(out, MAJOR_NEGATIVE, (-1 - n) as u64);
+    }
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_head(out, MAJOR_BYTES, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// A `COSE_Sign1` header for the `ES256K` algorithm.
+///
+/// The algorithm is always `ES256K` and is placed in the protected header; the key identifier, if
+/// any, is placed in the unprotected header. Other parameters of a parsed header are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Header {
+    kid: Option<Vec<u8>>,
+}
+
+impl Header {
+    /// Creates a header containing only the `ES256K` algorithm.
+    #[inline]
+    pub fn new() -> Self { Header::default() }
+
+    /// Sets the identifier of the signing key (`kid`).
+    pub fn with_kid(mut self, kid: &[u8]) -> Self {
+        self.kid = Some(kid.to_vec());
+        self
+    }
+
+    /// Returns the identifier of the signing key, if any.
+    #[inline]
+    pub fn kid(&self) -> Option<&[u8]> { self.kid.as_deref() }
+}
+
+/// The encoded protected header `{1: -47}`.
+fn protected_header() -> Vec<u8> {
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_MAP, 1);
+    write_int(&mut out, HEADER_ALG);
+    write_int(&mut out, ALGORITHM);
+    out
+}
+
+/// Computes the message signed for a `COSE_Sign1` with the given parts.
+fn message(protected: &[u8], external_aad: &[u8], payload: &[u8]) -> Message {
+    let mut sig_structure = Vec::new();
+    write_head(&mut sig_structure, MAJOR_ARRAY, 4);
+    write_head(&mut sig_structure, MAJOR_TEXT, 10);
+    sig_structure.extend_from_slice(b"Signature1");
+    write_bytes(&mut sig_structure, protected);
+    write_bytes(&mut sig_structure, external_aad);
+    write_bytes(&mut sig_structure, payload);
+    Message::from_digest(sha256::Hash::hash(&sig_structure).to_byte_array())
+}
+
+/// Signs `payload` with `sk`, returning a tagged `COSE_Sign1` message.
+///
+/// `external_aad` is additional data authenticated by the signature but not included in the
+/// message; pass an empty slice if there is none. The signature uses a deterministic RFC 6979
+/// nonce and has a low S value.
+pub fn sign1<C: Signing>(
+    secp: &Secp256k1<C>,
+    header: &Header,
+    payload: &[u8],
+    external_aad: &[u8],
+    sk: &SecretKey,
+) -> Vec<u8> {
+    let protected = protected_header();
+    let signature = secp.sign_ecdsa_low_r(message(&protected, external_aad, payload), sk);
+
+    let mut out = Vec::new();
+    write_head(&mut out, MAJOR_TAG, SIGN1_TAG);
+    write_head(&mut out, MAJOR_ARRAY, 4);
+    write_bytes(&mut out, &protected);
+    match header.kid {
+        Some(ref kid) => {
+            write_head(&mut out, MAJOR_MAP, 1);
+            write_int(&mut out, HEADER_KID);
+            write_bytes(&mut out, kid);
+        }
+        None => write_head(&mut out, MAJOR_MAP, 0),
+    }
+    write_bytes(&mut out, payload);
+    write_bytes(&mut out, &signature.serialize_compact());
+    out
+}
+
+/// Parses the header buckets of a `COSE_Sign1`, checking the algorithm and critical parameters.
+fn parse_header(protected: &[u8], unprotected: &mut Decoder) -> Result<Header, Error> {
+    let mut header = Header::new();
+    let mut algorithm = None;
+    if !protected.is_empty() {
+        let mut decoder = Decoder::new(protected);
+        for_each_entry(&mut decoder, |label, value| match label {
+            Label::Int(HEADER_ALG) => {
+                algorithm = Some(value.read_int()?);
+                Ok(())
+            }
+            Label::Int(HEADER_CRIT) => Err(Error::UnsupportedCriticalHeader),
+            Label::Int(HEADER_KID) => {
+                header.kid = Some(value.read_bytes()?.to_vec());
+                Ok(())
+            }
+            _ => value.skip(0),
+        })?;
+        decoder.finish()?;
+    }
+    if algorithm != Some(ALGORITHM) {
+        return Err(Error::UnsupportedAlgorithm);
+    }
+    for_each_entry(unprotected, |label, value| match label {
+        // The algorithm must be protected, and parameters must not appear in both buckets.
+        Label::Int(HEADER_ALG) | Label::Int(HEADER_CRIT) => Err(Error::Malformed),
+        Label::Int(HEADER_KID) if header.kid.is_some() => Err(Error::Malformed),
+        Label::Int(HEADER_KID) => {
+            header.kid = Some(value.read_bytes()?.to_vec());
+            Ok(())
+        }
+        _ => value.skip(0),
+    })?;
+    Ok(header)
+}
+
+/// Verifies a `COSE_Sign1` message against `pk`, returning its header and payload.
+///
+/// The message may be tagged or untagged. `external_aad` must match the value passed to
+/// [`sign1`].
+pub fn verify1<C: Verification>(
+    secp: &Secp256k1<C>,
+    cose_sign1: &[u8],
+    external_aad: &[u8],
+    pk: &PublicKey,
+) -> Result<(Header, Vec<u8>), Error> {
+    let mut decoder = Decoder::new(cose_sign1);
+    match decoder.peek_head()? {
+        (MAJOR_TAG, SIGN1_TAG) => {
+            decoder.read_head()?;
+        }
+        (MAJOR_TAG, _) => return Err(Error::Malformed),
+        _ => {}
+    }
+    if decoder.read_array()? != 4 {
+        return Err(Error::Malformed);
+    }
+    let protected = decoder.read_bytes()?;
+    let header = parse_header(protected, &mut decoder)?;
+    let payload = decoder.read_bytes()?;
+    let signature = decoder.read_bytes()?;
+    decoder.finish()?;
+
+    if signature.len() != 64 {
+        return Err(Error::InvalidSignature);
+    }
+    let signature = Signature::from_compact(signature).map_err(|_| Error::InvalidSignature)?;
+    let mut normalized = signature;
+    normalized.normalize_s();
+    if normalized != signature {
+        return Err(Error::HighS);
+    }
+    secp.verify_ecdsa(message(protected, external_aad, payload), &signature, pk)
+        .map_err(|_| Error::IncorrectSignature)?;
+    Ok((header, payload.to_vec()))
+}
+
+impl PublicKey {
+    /// Serializes the key as a `COSE_Key` with uncompressed coordinates.
+    ///
+    /// The encoding is `{1: 2, -1: 8, -2: x, -3: y}` (key type `EC2`, curve `secp256k1`) in
+    /// deterministic CBOR.
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        let serialized = self.serialize_uncompressed();
+        let mut out = Vec::new();
+        write_head(&mut out, MAJOR_MAP, 4);
+        write_int(&mut out, KEY_KTY);
+        write_int(&mut out, KEY_TYPE_EC2);
+        write_int(&mut out, KEY_CRV);
+        write_int(&mut out, CURVE);
+        write_int(&mut out, KEY_X);
+        write_bytes(&mut out, &serialized[1..33]);
+        write_int(&mut out, KEY_Y);
+        write_bytes(&mut out, &serialized[33..]);
+        out
+    }
+
+    /// Parses a `COSE_Key`, with the `y` coordinate given either in full or as its sign bit.
+    ///
+    /// Parameters other than the key type, curve and coordinates are ignored.
+    pub fn from_cose_key(cose_key: &[u8]) -> Result<PublicKey, Error> {
+        let mut decoder = Decoder::new(cose_key);
+        let (mut kty, mut crv, mut x, mut y, mut y_odd) = (None, None, None, None, None);
+        for_each_entry(&mut decoder, |label, value| {
+            match label {
+                Label::Int(KEY_KTY) => kty = Some(value.read_label()?),
+                Label::Int(KEY_CRV) => crv = Some(value.read_label()?),
+                Label::Int(KEY_X) => x = Some(value.read_bytes()?),
+                Label::Int(KEY_Y) => match value.peek_head()? {
+                    (MAJOR_SIMPLE, b @ (SIMPLE_FALSE | SIMPLE_TRUE)) => {
+                        value.read_head()?;
+                        y_odd = Some(b == SIMPLE_TRUE);
+                    }
+                    _ => y = Some(value.read_bytes()?),
+                },
+                _ => value.skip(0)?,
+            }
+            Ok(())
+        })?;
+        decoder.finish()?;
+
+        match kty.ok_or(Error::Malformed)? {
+            Label::Int(KEY_TYPE_EC2) => {}
+            _ => return Err(Error::UnsupportedKeyType),
+        }
+        match crv.ok_or(Error::Malformed)? {
+            Label::Int(CURVE) => {}
+            _ => return Err(Error::UnsupportedCurve),
+        }
+        let x = x.ok_or(Error::Malformed)?;
+        if x.len() != 32 {
+            return Err(Error::InvalidPublicKey);
+        }
+        match (y, y_odd) {
+            (Some(y), None) => {
+                if y.len() != 32 {
+                    return Err(Error::InvalidPublicKey);
+                }
+                let mut serialized = [0x04; constants::UNCOMPRESSED_PUBLIC_KEY_SIZE];
+                serialized[1..33].copy_from_slice(x);
+                serialized[33..].copy_from_slice(y);
+                PublicKey::from_byte_array_uncompressed(&serialized)
+            }
+            (None, Some(odd)) => {
+                let mut serialized = [if odd { 0x03 } else { 0x02 }; constants::PUBLIC_KEY_SIZE];
+                serialized[1..].copy_from_slice(x);
+                PublicKey::from_byte_array_compressed(&serialized)
+            }
+            _ => return Err(Error::Malformed),
+        }
+        .map_err(|_| Error::InvalidPublicKey)
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(secp256k1_fuzz))] // The vectors are real signatures, which fuzz-sigs can't reproduce.
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+    use crate::der::tests::secret_key;
+
+    // Signed by the Python `cryptography` package with the secret key sha256("der test"), over
+    // the payload "This is the content." with external AAD 11aa22bb33cc44dd55006699.
+    fn sign1_vector() -> [u8; 102] {
+        hex!("d28444a101382ea104456b65792d3154546869732069732074686520636f6e74656e742e58400c904045a9191dc16c23391f97e16129290f11dc72e3c3bd4f23483e1eb595b35677129e99e193073a76e4098c3bd89bd4cbe42925473c4f27aca70310715925")
+    }
+    const EXTERNAL_AAD: [u8; 12] =
+        [0x11, 0xaa, 0x22, 0xbb, 0x33, 0xcc, 0x44, 0xdd, 0x55, 0x00, 0x66, 0x99];
+    fn cose_key() -> [u8; 75] {
+        hex!("a40102200821582047287cba4a7d5cfcd31f1f24fe8ea994a8d383e686f157afa5a1944c8c84b5bc225820ded5ee458e776d9c674d302060dfce057f0b72dcc686e46abf519cff6a7b4069")
+    }
+
+    fn public_key() -> PublicKey { secret_key().public_key(&Secp256k1::new()) }
+
+    #[test]
+    fn cose_key_round_trip() {
+        let pk = public_key();
+        assert_eq!(pk.to_cose_key(), cose_key());
+        assert_eq!(PublicKey::from_cose_key(&cose_key()), Ok(pk));
+
+        // Compressed form with the sign bit of `y`, extra parameters and a different key order.
+        let compressed = hex!("a521582047287cba4a7d5cfcd31f1f24fe8ea994a8d383e686f157afa5a1944c8c84b5bc0102200802456b65792d3122f5");
+        assert_eq!(PublicKey::from_cose_key(&compressed), Ok(pk));
+        let mut even = compressed;
+        even[even.len() - 1] = 0xf4;
+        assert_eq!(PublicKey::from_cose_key(&even), Ok(pk.negate(&Secp256k1::verification_only())));
+    }
+
+    #[test]
+    fn cose_key_invalid() {
+        let mut okp = cose_key();
+        okp[2] = 0x01;
+        assert_eq!(PublicKey::from_cose_key(&okp), Err(Error::UnsupportedKeyType));
+        let mut p256 = cose_key();
+        p256[4] = 0x01;
+        assert_eq!(PublicKey::from_cose_key(&p256), Err(Error::UnsupportedCurve));
+        let mut off_curve = cose_key();
+        off_curve[74] ^= 1;
+        assert_eq!(PublicKey::from_cose_key(&off_curve), Err(Error::InvalidPublicKey));
+
+        let mut duplicate = cose_key().to_vec();
+        duplicate[0] = 0xa5;
+        duplicate.extend_from_slice(&hex!("0102"));
+        assert_eq!(PublicKey::from_cose_key(&duplicate), Err(Error::Malformed));
+        // Unknown integer parameters up to the maximum number of entries.
+        let with_entries = |n: usize| {
+            let mut key = vec![];
+            write_head(&mut key, MAJOR_MAP, n as u64);
+            key.extend_from_slice(&cose_key()[1..]);
+            for label in 10..(n as u64 + 6) {
+                write_head(&mut key, MAJOR_UNSIGNED, label);
+                key.push(0xf6);
+            }
+            PublicKey::from_cose_key(&key)
+        };
+        assert_eq!(with_entries(MAX_ENTRIES), Ok(public_key()));
+        assert_eq!(with_entries(MAX_ENTRIES + 1), Err(Error::Malformed));
+        let mut trailing = cose_key().to_vec();
+        trailing.push(0);
+        assert_eq!(PublicKey::from_cose_key(&trailing), Err(Error::Malformed));
+        // Non-shortest length encoding of `x`.
+        let mut long = hex!("a40102200821590020").to_vec();
+        long.extend_from_slice(&cose_key()[8..]);
+        assert_eq!(PublicKey::from_cose_key(&long), Err(Error::Malformed));
+        // Indefinite-length map.
+        let mut indefinite = cose_key().to_vec();
+        indefinite[0] = 0xbf;
+        indefinite.push(0xff);
+        assert_eq!(PublicKey::from_cose_key(&indefinite), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn sign1_interop() {
+        let secp = Secp256k1::verification_only();
+        let pk = public_key();
+        let (header, payload) = verify1(&secp, &sign1_vector(), &EXTERNAL_AAD, &pk).unwrap();
+        assert_eq!(header, Header::new().with_kid(b"key-1"));
+        assert_eq!(payload, b"This is the content.");
+
+        // Untagged.
+        assert!(verify1(&secp, &sign1_vector()[1..], &EXTERNAL_AAD, &pk).is_ok());
+        assert_eq!(verify1(&secp, &sign1_vector(), b"", &pk), Err(Error::IncorrectSignature));
+
+        // The same signature with S negated.
+        let mut high_s = sign1_vector();
+        high_s[70..].copy_from_slice(&hex!(
+            "a988ed61661e6cf8c5891bf673c42762e5e2f8bd8a0163ec9825b789bfc4e81c"
+        ));
+        assert_eq!(verify1(&secp, &high_s, &EXTERNAL_AAD, &pk), Err(Error::HighS));
+    }
+
+    #[test]
+    fn sign1_round_trip() {
+        let secp = Secp256k1::new();
+        let sk = secret_key();
+        let pk = sk.public_key(&secp);
+
+        for header in [Header::new(), Header::new().with_kid(b"key-1")] {
+            let message = sign1(&secp, &header, b"payload", &EXTERNAL_AAD, &sk);
+            assert_eq!(message, sign1(&secp, &header, b"payload", &EXTERNAL_AAD, &sk));
+            assert_eq!(
+                verify1(&secp, &message, &EXTERNAL_AAD, &pk),
+                Ok((header.clone(), b"payload".to_vec()))
+            );
+        }
+        // Everything but the signature matches the interop vector.
+        let header = Header::new().with_kid(b"key-1");
+        let message = sign1(&secp, &header, b"This is the content.", &EXTERNAL_AAD, &sk);
+        assert_eq!(message[..36], sign1_vector()[..36]);
+    }
+
+    #[test]
+    fn sign1_invalid_header() {
+        let secp = Secp256k1::verification_only();
+        let pk = public_key();
+        let with_headers = |protected: &[u8], unprotected: &[u8]| {
+            let mut message = hex!("d284").to_vec();
+            write_bytes(&mut message, protected);
+            message.extend_from_slice(unprotected);
+            message.extend_from_slice(&sign1_vector()[15..]);
+            verify1(&secp, &message, &EXTERNAL_AAD, &pk)
+        };
+
+        assert!(with_headers(&hex!("a101382e"), &hex!("a104456b65792d31")).is_ok());
+        // ES256 (-7), no algorithm, an empty protected header and the algorithm unprotected.
+        assert_eq!(with_headers(&hex!("a10126"), &hex!("a0")), Err(Error::UnsupportedAlgorithm));
+        assert_eq!(with_headers(&hex!("a0"), &hex!("a0")), Err(Error::UnsupportedAlgorithm));
+        assert_eq!(with_headers(&[], &hex!("a101382e")), Err(Error::UnsupportedAlgorithm));
+        assert_eq!(
+            with_headers(&hex!("a201382e028118ff"), &hex!("a0")),
+            Err(Error::UnsupportedCriticalHeader)
+        );
+        // The key identifier in both buckets.
+        assert_eq!(with_headers(&hex!("a201382e044161"), &hex!("a1044161")), Err(Error::Malformed));
+        // Unknown parameters are skipped; this only changes the signed data.
+        assert_eq!(
+            with_headers(&hex!("a201382e0381626162"), &hex!("a2044161636162638201f6")),
+            Err(Error::IncorrectSignature)
+        );
+        // Other tags.
+        let mut message = sign1_vector();
+        message[0] = 0xd1;
+        assert_eq!(verify1(&secp, &message, &EXTERNAL_AAD, &pk), Err(Error::Malformed));
+    }
+}
@@ -35,6 +35,14 @@ const SHARED_SECRET_SIZE: usize = constants::SECRET_KEY_SIZE;
 pub struct SharedSecret([u8; SHARED_SECRET_SIZE]);
 impl_display_secret!(SharedSecret);
 impl_non_secure_erase!(SharedSecret, 0, [0u8; SHARED_SECRET_SIZE]);
+impl_subtle!(SharedSecret);
+impl_zeroize_guard!(
+    /// A [`SharedSecret`] that is erased when dropped.
+    ///
+    /// See [`crate::SecretKeyGuard`] for details.
+    SharedSecretGuard,
+    SharedSecret
+);
 
 impl SharedSecret {
     /// Creates a new shared secret from a pubkey and secret key.
@@ -207,6 +215,28 @@ mod tests {
         assert!(sec_odd != sec2);
     }
 
+    #[test]
+    #[cfg(all(feature = "zeroize", feature = "alloc"))]
+    fn shared_secret_guard() {
+        use core::mem::MaybeUninit;
+
+        use crate::{PublicKey, SecretKey};
+
+        let s = Secp256k1::new();
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&s, &sk);
+        let secret = SharedSecret::new(&pk, &sk);
+
+        let guard = super::SharedSecretGuard::new(secret);
+        assert_eq!(guard.secret_bytes(), secret.secret_bytes());
+        let mut slot = MaybeUninit::new(guard);
+        let erased = unsafe {
+            slot.as_mut_ptr().drop_in_place();
+            (slot.as_ptr() as *const SharedSecret).read()
+        };
+        assert_eq!(erased.secret_bytes(), [0; 32]);
+    }
+
     #[test]
     fn test_c_callback() {
         let x = [5u8; 32];
@@ -3,17 +3,19 @@
 //! Structs and functionality related to the ECDSA signature algorithm.
 //!
 
+mod nonce;
 #[cfg(feature = "recovery")]
 mod recovery;
 pub mod serialized_signature;
 
 use core::{fmt, ptr, str};
 
+pub use self::nonce::Rfc6979;
 #[cfg(feature = "recovery")]
 pub use self::recovery::{RecoverableSignature, RecoveryId};
 pub use self::serialized_signature::SerializedSignature;
 use crate::ffi::CPtr;
-#[cfg(feature = "global-context")]
+#[cfg(feature = "global-context-no-std")]
 use crate::SECP256K1;
 use crate::{
     ffi, from_hex, Error, Message, PublicKey, Secp256k1, SecretKey, Signing, Verification,
@@ -193,7 +195,7 @@ impl Signature {
     /// Verifies an ECDSA signature for `msg` using `pk` and the global [`SECP256K1`] context.
     /// The signature must be normalized or verification will fail (see [`Signature::normalize_s`]).
     #[inline]
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
     pub fn verify(&self, msg: impl Into<Message>, pk: &PublicKey) -> Result<(), Error> {
         SECP256K1.verify_ecdsa(msg, self, pk)
     }
@@ -0,0 +1,316 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides ECDSA signing with user-supplied nonce generation functions.
+//!
+//! Ordinary users never need this; [`Secp256k1::sign_ecdsa`] uses RFC6979 deterministic nonces,
+//! which is what almost everyone wants. Custom nonce functions exist to reproduce signatures made
+//! by other implementations (e.g. hardware devices using non-RFC6979 deterministic nonces).
+//!
+//! **Using a badly designed nonce function will leak your secret key.** Reusing a nonce for two
+//! different messages, or using a predictable or biased nonce, lets anyone who sees the signatures
+//! compute the secret key.
+//!
+
+use core::ptr;
+
+use crate::callback::PanicGuard;
+use crate::ecdsa::Signature;
+use crate::ffi::types::{c_int, c_uchar, c_uint, c_void};
+use crate::ffi::{self, CPtr};
+use crate::{Error, Message, Secp256k1, SecretKey, Signing};
+
+/// The RFC6979 nonce generation function used by [`Secp256k1::sign_ecdsa`].
+///
+/// This is the building block for custom nonce functions that wrap, or fall back to, the default
+/// behavior. See [`Secp256k1::sign_ecdsa_with_nonce_fn`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rfc6979 {
+    extra_data: Option<[u8; 32]>,
+}
+
+impl Rfc6979 {
+    /// Creates the plain RFC6979 nonce function, as used by [`Secp256k1::sign_ecdsa`].
+    #[inline]
+    pub const fn new() -> Self { Rfc6979 { extra_data: None } }
+
+    /// Creates an RFC6979 nonce function that mixes 32 bytes of `extra_data` into the nonce
+    /// derivation, as done by [`Secp256k1::sign_ecdsa_with_noncedata`].
+    #[inline]
+    pub const fn with_extra_data(extra_data: [u8; 32]) -> Self {
+        Rfc6979 { extra_data: Some(extra_data) }
+    }
+
+    /// Returns the extra data mixed into the nonce derivation, if any.
+    #[inline]
+    pub fn extra_data(&self) -> Option<&[u8; 32]> { self.extra_data.as_ref() }
+
+    /// Computes the nonce for the `attempt`-th signing attempt of `msg` with secret key `key`.
+    ///
+    /// The arguments mirror those passed to the closure of
+    /// [`Secp256k1::sign_ecdsa_with_nonce_fn`]. Returns `None` if libsecp256k1 fails to derive a
+    /// nonce, which does not happen for valid inputs.
+    pub fn nonce(
+        &self,
+        msg: &[u8; 32],
+        key: &[u8; 32],
+        algo: Option<&[u8; 16]>,
+        attempt: u32,
+    ) -> Option<[u8; 32]> {
+        let mut nonce = [0u8; 32];
+        let algo_ptr = match algo {
+            Some(algo) => algo.as_ptr(),
+            None => ptr::null(),
+        };
+        let data_ptr = match self.extra_data {
+            Some(ref data) => data.as_c_ptr() as *mut c_void,
+            None => ptr::null_mut(),
+        };
+        unsafe {
+            let nonce_fn = ffi::secp256k1_nonce_function_rfc6979
+                .expect("libsecp256k1 always provides the RFC6979 nonce function");
+            let ret = nonce_fn(
+                nonce.as_mut_c_ptr(),
+                msg.as_c_ptr(),
+                key.as_c_ptr(),
+                algo_ptr,
+                data_ptr,
+                attempt,
+            );
+            if ret == 1 {
+                Some(nonce)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// State handed to libsecp256k1 as the `data` pointer of [`nonce_fn_trampoline`].
+struct NonceFnState<'a, F> {
+    nonce_fn: F,
+    data: Option<&'a [u8; 32]>,
+    guard: PanicGuard,
+}
+
+/// Calls the Rust closure stored in `data` on behalf of `secp256k1_ecdsa_sign`.
+unsafe extern "C" fn nonce_fn_trampoline<F>(
+    nonce32: *mut c_uchar,
+    msg32: *const c_uchar,
+    key32: *const c_uchar,
+    algo16: *const c_uchar,
+    data: *mut c_void,
+    attempt: c_uint,
+) -> c_int
+where
+    F: FnMut(&[u8; 32], &[u8; 32], Option<&[u8; 16]>, Option<&[u8; 32]>, u32) -> Option<[u8; 32]>,
+{
+    let state = &mut *(data as *mut NonceFnState<F>);
+    let msg = &*(msg32 as *const [u8; 32]);
+    let key = &*(key32 as *const [u8; 32]);
+    let algo = (algo16 as *const [u8; 16]).as_ref();
+    let user_data = state.data;
+    let nonce_fn = &mut state.nonce_fn;
+
+    match state.guard.call(None, || nonce_fn(msg, key, algo, user_data, attempt)) {
+        Some(nonce) => {
+            ptr::copy_nonoverlapping(nonce.as_ptr(), nonce32, 32);
+            1
+        }
+        None => 0,
+    }
+}
+
+impl<C: Signing> Secp256k1<C> {
+    /// Constructs a signature for `msg` using the secret key `sk` and a custom nonce function.
+    ///
+    /// `nonce_fn` is called with the message, the secret key, an algorithm tag (always `None` for
+    /// ECDSA), `noncedata` and an attempt counter. It returns the 32-byte nonce, or `None` to abort
+    /// signing. If the returned nonce is zero, overflows the curve order, or results in an invalid
+    /// signature, `nonce_fn` is called again with the attempt counter incremented.
+    ///
+    /// Use [`Rfc6979`] to reproduce the default nonce derivation from inside `nonce_fn`.
+    ///
+    /// **Security warning:** a badly designed nonce function will leak your secret key. Reusing a
+    /// nonce for two different messages, or using a predictable or biased nonce, lets anyone who
+    /// sees the signatures compute the secret key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NonceGenerationFailed`] if `nonce_fn` returns `None`.
+    ///
+    /// # Panics
+    ///
+    /// If `nonce_fn` panics the panic is propagated once control returns from libsecp256k1. This
+    /// requires the `std` feature; without it a panic in `nonce_fn` must not unwind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "alloc", not(secp256k1_fuzz)))] {
+    /// use secp256k1::ecdsa::Rfc6979;
+    /// use secp256k1::{Message, Secp256k1, SecretKey};
+    ///
+    /// let secp = Secp256k1::new();
+    /// let sk = SecretKey::from_byte_array(&[0xcd; 32]).expect("within curve order");
+    /// let msg = Message::from_digest([0xab; 32]);
+    ///
+    /// let sig = secp
+    ///     .sign_ecdsa_with_nonce_fn(msg, &sk, None, |msg, key, algo, _data, attempt| {
+    ///         Rfc6979::new().nonce(msg, key, algo, attempt)
+    ///     })
+    ///     .expect("RFC6979 never fails");
+    /// assert_eq!(sig, secp.sign_ecdsa(msg, &sk));
+    /// # }
+    /// ```
+    pub fn sign_ecdsa_with_nonce_fn<F>(
+        &self,
+        msg: impl Into<Message>,
+        sk: &SecretKey,
+        noncedata: Option<&[u8; 32]>,
+        nonce_fn: F,
+    ) -> Result<Signature, Error>
+    where
+        F: FnMut(
+            &[u8; 32],
+            &[u8; 32],
+            Option<&[u8; 16]>,
+            Option<&[u8; 32]>,
+            u32,
+        ) -> Option<[u8; 32]>,
+    {
+        let msg = msg.into();
+        let mut state = NonceFnState { nonce_fn, data: noncedata, guard: PanicGuard::default() };
+        let ret = unsafe {
+            let mut sig = ffi::Signature::new();
+            let ret = ffi::secp256k1_ecdsa_sign(
+                self.ctx.as_ptr(),
+                &mut sig,
+                msg.as_c_ptr(),
+                sk.as_c_ptr(),
+                Some(nonce_fn_trampoline::<F>),
+                &mut state as *mut NonceFnState<F> as *const c_void,
+            );
+            if ret == 1 {
+                Ok(Signature::from(sig))
+            } else {
+                Err(Error::NonceGenerationFailed)
+            }
+        };
+        state.guard.resume();
+        ret
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(secp256k1_fuzz))] // fuzz-sigs ignore the nonce function
+#[cfg(feature = "alloc")]
+mod tests {
+    use core::str::FromStr;
+
+    use hex_lit::hex;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use super::*;
+    use crate::{constants, PublicKey};
+
+    fn test_key() -> SecretKey {
+        SecretKey::from_str("57f0148f94d13095cfda539d0da0d1541304b678d8b36e243980aab4e1b7cead")
+            .unwrap()
+    }
+
+    fn test_msg() -> Message {
+        Message::from_digest(hex!(
+            "887d04bb1cf1b1554f1b268dfe62d13064ca67ae45348d50d1392ce2d13418ac"
+        ))
+    }
+
+    #[test]
+    fn rfc6979_matches_sign_ecdsa() {
+        let secp = Secp256k1::new();
+        let (msg, sk) = (test_msg(), test_key());
+
+        let sig = secp
+            .sign_ecdsa_with_nonce_fn(msg, &sk, None, |msg, key, algo, data, attempt| {
+                assert!(algo.is_none());
+                assert!(data.is_none());
+                Rfc6979::new().nonce(msg, key, algo, attempt)
+            })
+            .unwrap();
+        assert_eq!(sig, secp.sign_ecdsa(msg, &sk));
+    }
+
+    #[test]
+    fn rfc6979_extra_data_matches_sign_ecdsa_with_noncedata() {
+        let secp = Secp256k1::new();
+        let (msg, sk) = (test_msg(), test_key());
+        let noncedata = [42u8; 32];
+        let rfc6979 = Rfc6979::with_extra_data(noncedata);
+        assert_eq!(rfc6979.extra_data(), Some(&noncedata));
+
+        let sig = secp
+            .sign_ecdsa_with_nonce_fn(
+                msg,
+                &sk,
+                Some(&noncedata),
+                |msg, key, algo, data, attempt| {
+                    assert_eq!(data, Some(&noncedata));
+                    rfc6979.nonce(msg, key, algo, attempt)
+                },
+            )
+            .unwrap();
+        assert_eq!(sig, secp.sign_ecdsa_with_noncedata(msg, &sk, &noncedata));
+    }
+
+    #[test]
+    fn fixed_nonce() {
+        let secp = Secp256k1::new();
+        let (msg, sk) = (test_msg(), test_key());
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+
+        // With nonce 1 the signature's R value is the generator's x coordinate.
+        let sig = secp
+            .sign_ecdsa_with_nonce_fn(msg, &sk, None, |_, _, _, _, _| Some(constants::ONE))
+            .unwrap();
+        assert_eq!(sig.serialize_compact()[..32], constants::GENERATOR_X);
+        assert!(secp.verify_ecdsa(msg, &sig, &pk).is_ok());
+    }
+
+    #[test]
+    fn invalid_nonce_is_retried() {
+        let secp = Secp256k1::new();
+        let (msg, sk) = (test_msg(), test_key());
+
+        let mut attempts = [None; 3];
+        let sig = secp
+            .sign_ecdsa_with_nonce_fn(msg, &sk, None, |_, _, _, _, attempt| {
+                attempts[attempt as usize] = Some(attempt);
+                match attempt {
+                    0 => Some([0u8; 32]),
+                    1 => Some([0xff; 32]),
+                    _ => Some(constants::ONE),
+                }
+            })
+            .unwrap();
+        assert_eq!(attempts, [Some(0), Some(1), Some(2)]);
+        assert_eq!(sig.serialize_compact()[..32], constants::GENERATOR_X);
+    }
+
+    #[test]
+    fn failing_nonce_fn() {
+        let secp = Secp256k1::new();
+        let res =
+            secp.sign_ecdsa_with_nonce_fn(test_msg(), &test_key(), None, |_, _, _, _, _| None);
+        assert_eq!(res, Err(Error::NonceGenerationFailed));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[should_panic(expected = "nonce function panicked")]
+    fn panicking_nonce_fn() {
+        let secp = Secp256k1::new();
+        let _ = secp.sign_ecdsa_with_nonce_fn(test_msg(), &test_key(), None, |_, _, _, _, _| {
+            panic!("nonce function panicked")
+        });
+    }
+}
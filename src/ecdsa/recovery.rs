@@ -4,13 +4,16 @@
 //! signature.
 //!
 
-use core::ptr;
+use core::{fmt, ptr, str};
 
 use self::super_ffi::CPtr;
 use super::ffi as super_ffi;
 use crate::ecdsa::Signature;
 use crate::ffi::recovery as ffi;
-use crate::{key, Error, Message, Secp256k1, Signing, Verification};
+use crate::{from_hex, key, Error, Message, Secp256k1, Signing, Verification};
+
+/// The size of a recoverable signature in compact format followed by its recovery ID.
+const RECOVERABLE_SIGNATURE_SIZE: usize = 65;
 
 /// A tag used for recovering the public key from a compact signature.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -51,6 +54,42 @@ impl From<RecoveryId> for i32 {
     }
 }
 
+impl fmt::Display for RecoveryId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(&i32::from(*self), f) }
+}
+
+impl str::FromStr for RecoveryId {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<RecoveryId, Error> {
+        match s {
+            "0" => Ok(RecoveryId::Zero),
+            "1" => Ok(RecoveryId::One),
+            "2" => Ok(RecoveryId::Two),
+            "3" => Ok(RecoveryId::Three),
+            _ => Err(Error::InvalidRecoveryId),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RecoveryId {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_u8(i32::from(*self) as u8)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RecoveryId {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        use serde::de::{Error as _, Unexpected};
+
+        let id = <u8 as serde::Deserialize>::deserialize(d)?;
+        RecoveryId::try_from(i32::from(id)).map_err(|_| {
+            D::Error::invalid_value(Unexpected::Unsigned(id.into()), &"a recovery ID from 0 to 3")
+        })
+    }
+}
+
 /// An ECDSA signature with a recovery ID for pubkey recovery.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Ord, PartialOrd)]
 pub struct RecoverableSignature(ffi::RecoverableSignature);
@@ -132,10 +171,80 @@ impl RecoverableSignature {
     /// Determines the public key for which this [`Signature`] is valid for `msg`. Requires a
     /// verify-capable context.
     #[inline]
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
     pub fn recover(&self, msg: impl Into<Message>) -> Result<key::PublicKey, Error> {
         crate::SECP256K1.recover_ecdsa(msg, self)
     }
+
+    /// Serializes the signature in compact format followed by a byte holding the recovery ID,
+    /// as used by its `Display` and serde encodings.
+    fn to_bytes(self) -> [u8; RECOVERABLE_SIGNATURE_SIZE] {
+        let (recid, compact) = self.serialize_compact();
+        let mut bytes = [0u8; RECOVERABLE_SIGNATURE_SIZE];
+        bytes[..64].copy_from_slice(&compact);
+        bytes[64] = i32::from(recid) as u8;
+        bytes
+    }
+
+    /// Parses a signature in compact format followed by a byte holding the recovery ID.
+    fn from_bytes(data: &[u8]) -> Result<RecoverableSignature, Error> {
+        if data.len() != RECOVERABLE_SIGNATURE_SIZE {
+            return Err(Error::InvalidSignature);
+        }
+        let recid = RecoveryId::try_from(i32::from(data[64]))?;
+        RecoverableSignature::from_compact(&data[..64], recid)
+    }
+}
+
+impl fmt::LowerHex for RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.to_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::LowerHex::fmt(self, f) }
+}
+
+impl str::FromStr for RecoverableSignature {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<RecoverableSignature, Error> {
+        let mut res = [0u8; RECOVERABLE_SIGNATURE_SIZE];
+        match from_hex(s, &mut res) {
+            Ok(RECOVERABLE_SIGNATURE_SIZE) => RecoverableSignature::from_bytes(&res),
+            _ => Err(Error::InvalidSignature),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RecoverableSignature {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_bytes(&self.to_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RecoverableSignature {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        if d.is_human_readable() {
+            d.deserialize_str(crate::serde_util::FromStrVisitor::new(
+                "a hex string representing a 65 byte recoverable signature",
+            ))
+        } else {
+            d.deserialize_bytes(crate::serde_util::BytesVisitor::new(
+                "raw 65 bytes recoverable signature",
+                RecoverableSignature::from_bytes,
+            ))
+        }
+    }
 }
 
 impl CPtr for RecoverableSignature {
@@ -429,6 +538,31 @@ mod tests {
         assert_eq!(&bytes_in[..], &bytes_out[..]);
     }
 
+    #[test]
+    fn test_recov_sig_to_from_str() {
+        let s = "6673ffad2147741f04772b6f921f0ba6af0c1e77fc439e65c36dedf4092e88984c1a971652e0ada880120ef8025e709fff2080c4a39aae068d12eed009b68c8901";
+        let sig = s.parse::<RecoverableSignature>().unwrap();
+        assert_eq!(sig.serialize_compact().0, RecoveryId::One);
+        assert_eq!(sig.to_string(), s);
+        assert_eq!(format!("{:x}", sig), s);
+
+        assert_eq!("".parse::<RecoverableSignature>(), Err(Error::InvalidSignature));
+        assert_eq!(s[..128].parse::<RecoverableSignature>(), Err(Error::InvalidSignature));
+        assert_eq!(
+            format!("{}00", s).parse::<RecoverableSignature>(),
+            Err(Error::InvalidSignature)
+        );
+        let bad_recid = format!("{}04", &s[..128]);
+        assert_eq!(bad_recid.parse::<RecoverableSignature>(), Err(Error::InvalidRecoveryId));
+
+        for id in [RecoveryId::Zero, RecoveryId::One, RecoveryId::Two, RecoveryId::Three] {
+            assert_eq!(id.to_string().parse::<RecoveryId>(), Ok(id));
+        }
+        for s in ["", "4", "-1", "01", "+1"] {
+            assert_eq!(s.parse::<RecoveryId>(), Err(Error::InvalidRecoveryId));
+        }
+    }
+
     #[test]
     fn test_recov_id_conversion_between_i32() {
         assert!(RecoveryId::try_from(-1i32).is_err());
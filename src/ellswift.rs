@@ -268,6 +268,15 @@ impl ElligatorSwift {
 /// private key.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ElligatorSwiftSharedSecret([u8; 32]);
+impl_non_secure_erase!(ElligatorSwiftSharedSecret, 0, [0u8; 32]);
+impl_subtle!(ElligatorSwiftSharedSecret);
+impl_zeroize_guard!(
+    /// An [`ElligatorSwiftSharedSecret`] that is erased when dropped.
+    ///
+    /// See [`crate::SecretKeyGuard`] for details.
+    ElligatorSwiftSharedSecretGuard,
+    ElligatorSwiftSharedSecret
+);
 
 impl ElligatorSwiftSharedSecret {
     /// Creates shared secret from bytes.
@@ -363,17 +372,20 @@ impl ffi::CPtr for ElligatorSwift {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(not(secp256k1_fuzz))]
     use core::str::FromStr;
 
     use crate::ellswift::ElligatorSwift;
-    #[cfg(all(not(secp256k1_fuzz), feature = "alloc"))]
+    #[cfg(feature = "alloc")]
     use crate::ellswift::{ElligatorSwiftSharedSecret, Party};
-    #[cfg(all(not(secp256k1_fuzz), feature = "alloc"))]
+    use crate::PublicKey;
+    #[cfg(feature = "alloc")]
     use crate::SecretKey;
-    use crate::{from_hex, PublicKey, XOnlyPublicKey};
+    #[cfg(not(secp256k1_fuzz))]
+    use crate::{from_hex, XOnlyPublicKey};
 
     #[test]
-    #[cfg(all(not(secp256k1_fuzz), feature = "alloc"))]
+    #[cfg(feature = "alloc")]
     fn test_elligator_swift_rtt() {
         // Test that we can round trip an ElligatorSwift encoding
         let secp = crate::Secp256k1::new();
@@ -385,7 +397,7 @@ mod tests {
         assert_eq!(pk, public_key);
     }
     #[test]
-    #[cfg(all(not(secp256k1_fuzz), feature = "alloc"))]
+    #[cfg(feature = "alloc")]
     fn test_create_elligator_swift_create_rtt() {
         // Test that we can round trip an ElligatorSwift created from a secret key
         let secp = crate::Secp256k1::new();
@@ -398,7 +410,7 @@ mod tests {
         assert_eq!(pk, expected);
     }
     #[test]
-    #[cfg(all(not(secp256k1_fuzz), feature = "alloc"))]
+    #[cfg(feature = "alloc")]
     fn test_xdh_with_custom_hasher() {
         // Test the ECDH with a custom hash function
         let secp = crate::Secp256k1::new();
@@ -0,0 +1,747 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides [EIP-712] hashing and signing of typed structured data.
+//!
+//! A message is a value of a struct type described by a [`Types`] schema. It is signed together
+//! with a [`Domain`] that binds the signature to an application, so that it cannot be replayed
+//! elsewhere. The signed digest is `keccak256(0x19 || 0x01 || domainSeparator || hashStruct(message))`.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "ethereum", feature = "std"))] {
+//! use secp256k1::ethereum::eip712::{Domain, TypedData, Types, Value};
+//! use secp256k1::ethereum::Address;
+//! use secp256k1::{Secp256k1, SecretKey};
+//!
+//! let secp = Secp256k1::new();
+//! let sk = SecretKey::from_byte_array(&[0xcd; 32]).expect("valid secret key");
+//! let address = Address::from_public_key(&sk.public_key(&secp));
+//!
+//! let domain = Domain::new().with_name("Bridge").with_version("1").with_chain_id(1);
+//! let types = Types::new()
+//!     .with_struct("Attestation", &[("subject", "address"), ("amount", "uint256")]);
+//! let message = Value::from_fields([("subject", address.into()), ("amount", 1000u64.into())]);
+//!
+//! let data = TypedData::new(domain, types, "Attestation", message);
+//! let sig = data.sign(&secp, &sk).unwrap();
+//! assert!(data.verify(&secp, &sig, &address).is_ok());
+//! # }
+//! ```
+//!
+//! [EIP-712]: https://eips.ethereum.org/EIPS/eip-712
+//!
+
+use core::fmt;
+
+use super::keccak::{self, Keccak256};
+use super::Address;
+use crate::alloc::collections::BTreeMap;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+use crate::ecdsa::RecoverableSignature;
+use crate::{Message, Secp256k1, SecretKey, Signing, Verification};
+
+/// The name of the struct type of the domain.
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Error hashing, signing or verifying typed data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// A type is neither a known struct nor a valid atomic or array type.
+    UnknownType,
+    /// A value does not have the kind of its type, such as a string for a `bool`.
+    TypeMismatch,
+    /// An integer does not fit its type, or a fixed-size array or `bytesN` value has the wrong
+    /// length.
+    OutOfRange,
+    /// A struct value lacks one of the fields of its type.
+    MissingField,
+    /// A struct value has a field its type does not declare.
+    UnknownField,
+    /// The signature is invalid or does not recover a public key.
+    InvalidSignature,
+    /// The signature was made by a different address.
+    IncorrectSignature,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            UnknownType => f.write_str("unknown EIP-712 type"),
+            TypeMismatch => f.write_str("value does not match its EIP-712 type"),
+            OutOfRange => f.write_str("value is out of range for its EIP-712 type"),
+            MissingField => f.write_str("struct value is missing a field"),
+            UnknownField => f.write_str("struct value has an undeclared field"),
+            InvalidSignature => f.write_str("invalid signature"),
+            IncorrectSignature => f.write_str("signature was made by a different address"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A value of an EIP-712 type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// A `bool` value.
+    Bool(bool),
+    /// An `address` value.
+    Address(Address),
+    /// A non-negative integer for a `uintN` or `intN` type, as a 256-bit big-endian word.
+    Uint([u8; 32]),
+    /// An integer for an `intN` type, as a 256-bit big-endian two's complement word.
+    Int([u8; 32]),
+    /// A `bytes` or `bytesN` value.
+    Bytes(Vec<u8>),
+    /// A `string` value.
+    String(String),
+    /// A value of an array type `T[]` or `T[n]`.
+    Array(Vec<Value>),
+    /// A value of a struct type, as its fields by name.
+    Struct(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Constructs a struct value from its fields by name.
+    pub fn from_fields<'a, I: IntoIterator<Item = (&'a str, Value)>>(fields: I) -> Self {
+        Value::Struct(fields.into_iter().map(|(name, value)| (name.into(), value)).collect())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self { Value::Bool(value) }
+}
+
+impl From<Address> for Value {
+    fn from(value: Address) -> Self { Value::Address(value) }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self { Value::from(u128::from(value)) }
+}
+
+impl From<u128> for Value {
+    fn from(value: u128) -> Self {
+        let mut word = [0u8; 32];
+        word[16..].copy_from_slice(&value.to_be_bytes());
+        Value::Uint(word)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        let mut word = if value < 0 { [0xff; 32] } else { [0; 32] };
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        Value::Int(word)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self { Value::String(value.into()) }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self { Value::String(value) }
+}
+
+/// A schema of named struct types.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Types {
+    /// The fields of each struct type, as `(name, type)` pairs.
+    structs: BTreeMap<String, Vec<(String, String)>>,
+}
+
+impl Types {
+    /// Constructs an empty schema.
+    pub fn new() -> Self { Types::default() }
+
+    /// Adds a struct type with the given fields, as `(name, type)` pairs in declaration order.
+    pub fn with_struct(mut self, name: &str, fields: &[(&str, &str)]) -> Self {
+        let fields = fields.iter().map(|&(name, ty)| (name.into(), ty.into())).collect();
+        self.structs.insert(name.into(), fields);
+        self
+    }
+
+    /// Returns the encoding of struct type `name`: its own signature followed by those of the
+    /// struct types it references, sorted by name.
+    pub fn encode_type(&self, name: &str) -> Result<String, Error> {
+        if !self.structs.contains_key(name) {
+            return Err(Error::UnknownType);
+        }
+        let mut dependencies = Vec::new();
+        self.collect_dependencies(name, &mut dependencies)?;
+        dependencies[1..].sort_unstable();
+
+        let mut out = String::new();
+        for name in dependencies {
+            out.push_str(name);
+            out.push('(');
+            for (i, (field, ty)) in self.structs[name].iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(ty);
+                out.push(' ');
+                out.push_str(field);
+            }
+            out.push(')');
+        }
+        Ok(out)
+    }
+
+    /// Returns the hash of the encoding of struct type `name`.
+    pub fn type_hash(&self, name: &str) -> Result<[u8; 32], Error> {
+        Ok(keccak::hash(self.encode_type(name)?.as_bytes()))
+    }
+
+    /// Computes `hashStruct` of `value`, a value of struct type `name`.
+    pub fn hash_struct(&self, name: &str, value: &Value) -> Result<[u8; 32], Error> {
+        let fields = self.structs.get(name).ok_or(Error::UnknownType)?;
+        let members = match value {
+            Value::Struct(members) => members,
+            _ => return Err(Error::TypeMismatch),
+        };
+        if members.iter().any(|(member, _)| !fields.iter().any(|(field, _)| field == member)) {
+            return Err(Error::UnknownField);
+        }
+
+        let mut hasher = Keccak256::new();
+        hasher.update(&self.type_hash(name)?);
+        for (field, ty) in fields {
+            let (_, value) =
+                members.iter().find(|(member, _)| member == field).ok_or(Error::MissingField)?;
+            hasher.update(&self.encode_value(ty, value)?);
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Adds struct type `ty`, stripped of array suffixes, and the struct types it references to
+    /// `found` if they are not already present.
+    fn collect_dependencies<'a>(&'a self, ty: &str, found: &mut Vec<&'a str>) -> Result<(), Error> {
+        let mut base = ty;
+        while let Some((element, _)) = split_array(base)? {
+            base = element;
+        }
+        match self.structs.get_key_value(base) {
+            Some((name, fields)) =>
+                if !found.contains(&name.as_str()) {
+                    found.push(name);
+                    for (_, ty) in fields {
+                        self.collect_dependencies(ty, found)?;
+                    }
+                },
+            None => {
+                Atomic::parse(base).ok_or(Error::UnknownType)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes `value` of type `ty` as a 32-byte word.
+    fn encode_value(&self, ty: &str, value: &Value) -> Result<[u8; 32], Error> {
+        if let Some((element, len)) = split_array(ty)? {
+            let elements = match value {
+                Value::Array(elements) => elements,
+                _ => return Err(Error::TypeMismatch),
+            };
+            if matches!(len, Some(len) if len != elements.len()) {
+                return Err(Error::OutOfRange);
+            }
+            let mut hasher = Keccak256::new();
+            for element_value in elements {
+                hasher.update(&self.encode_value(element, element_value)?);
+            }
+            return Ok(hasher.finalize());
+        }
+        if self.structs.contains_key(ty) {
+            return self.hash_struct(ty, value);
+        }
+        Atomic::parse(ty).ok_or(Error::UnknownType)?.encode(value)
+    }
+}
+
+/// An atomic or dynamic non-struct type.
+#[derive(Copy, Clone)]
+enum Atomic {
+    Bool,
+    Address,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+}
+
+impl Atomic {
+    fn parse(ty: &str) -> Option<Atomic> {
+        let size = |digits: &str| -> Option<usize> {
+            if digits.starts_with('0') || !digits.bytes().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            digits.parse().ok()
+        };
+        match ty {
+            "bool" => Some(Atomic::Bool),
+            "address" => Some(Atomic::Address),
+            "bytes" => Some(Atomic::Bytes),
+            "string" => Some(Atomic::String),
+            _ =>
+                if let Some(bits) = ty.strip_prefix("uint") {
+                    size(bits).filter(|&n| n % 8 == 0 && (8..=256).contains(&n)).map(Atomic::Uint)
+                } else if let Some(bits) = ty.strip_prefix("int") {
+                    size(bits).filter(|&n| n % 8 == 0 && (8..=256).contains(&n)).map(Atomic::Int)
+                } else if let Some(len) = ty.strip_prefix("bytes") {
+                    size(len).filter(|&n| (1..=32).contains(&n)).map(Atomic::FixedBytes)
+                } else {
+                    None
+                },
+        }
+    }
+
+    fn encode(self, value: &Value) -> Result<[u8; 32], Error> {
+        let mut word = [0u8; 32];
+        match (self, value) {
+            (Atomic::Bool, Value::Bool(b)) => word[31] = u8::from(*b),
+            (Atomic::Address, Value::Address(address)) => word[12..].copy_from_slice(&address.0),
+            (Atomic::Uint(bits), Value::Uint(n) | Value::Int(n)) => {
+                // An `Int` is only valid here if it is non-negative.
+                if !fits(n, bits, 0x00) {
+                    return Err(Error::OutOfRange);
+                }
+                word = *n;
+            }
+            (Atomic::Int(bits), Value::Uint(n)) => {
+                if !fits(n, bits - 1, 0x00) {
+                    return Err(Error::OutOfRange);
+                }
+                word = *n;
+            }
+            (Atomic::Int(bits), Value::Int(n)) => {
+                let sign = if n[0] & 0x80 == 0 { 0x00 } else { 0xff };
+                if !fits(n, bits - 1, sign) {
+                    return Err(Error::OutOfRange);
+                }
+                word = *n;
+            }
+            (Atomic::FixedBytes(len), Value::Bytes(bytes)) => {
+                if bytes.len() != len {
+                    return Err(Error::OutOfRange);
+                }
+                word[..len].copy_from_slice(bytes);
+            }
+            (Atomic::Bytes, Value::Bytes(bytes)) => word = keccak::hash(bytes),
+            (Atomic::String, Value::String(s)) => word = keccak::hash(s.as_bytes()),
+            _ => return Err(Error::TypeMismatch),
+        }
+        Ok(word)
+    }
+}
+
+/// Returns whether all bits of `word` above the low `bits` bits equal those of `fill`.
+fn fits(word: &[u8; 32], bits: usize, fill: u8) -> bool {
+    let high_bits = 256 - bits;
+    let (full, partial) = (high_bits / 8, high_bits % 8);
+    if word[..full].iter().any(|&byte| byte != fill) {
+        return false;
+    }
+    partial == 0 || (word[full] ^ fill) >> (8 - partial) == 0
+}
+
+/// Splits an array type `T[]` or `T[n]` into `T` and its length, if any.
+fn split_array(ty: &str) -> Result<Option<(&str, Option<usize>)>, Error> {
+    let inner = match ty.strip_suffix(']') {
+        Some(inner) => inner,
+        None => return Ok(None),
+    };
+    let (element, len) = inner.rsplit_once('[').ok_or(Error::UnknownType)?;
+    if len.is_empty() {
+        return Ok(Some((element, None)));
+    }
+    if len.starts_with('0') || !len.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(Error::UnknownType);
+    }
+    let len = len.parse().map_err(|_| Error::UnknownType)?;
+    Ok(Some((element, Some(len))))
+}
+
+/// The domain of a signature, binding it to an application.
+///
+/// Only the fields that are set are part of the domain separator.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Domain {
+    name: Option<String>,
+    version: Option<String>,
+    chain_id: Option<u64>,
+    verifying_contract: Option<Address>,
+    salt: Option<[u8; 32]>,
+}
+
+impl Domain {
+    /// Constructs a domain without any fields.
+    pub fn new() -> Self { Domain::default() }
+
+    /// Sets the name of the application.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the version of the application.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Sets the EIP-155 chain id.
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets the address of the contract that verifies the signature.
+    pub fn with_verifying_contract(mut self, address: Address) -> Self {
+        self.verifying_contract = Some(address);
+        self
+    }
+
+    /// Sets a salt to disambiguate the application.
+    pub fn with_salt(mut self, salt: [u8; 32]) -> Self {
+        self.salt = Some(salt);
+        self
+    }
+
+    /// Computes the domain separator, `hashStruct` of the domain.
+    pub fn separator(&self) -> [u8; 32] {
+        let mut fields = Vec::new();
+        let mut values = Vec::new();
+        if let Some(ref name) = self.name {
+            fields.push(("name", "string"));
+            values.push(("name", Value::from(name.as_str())));
+        }
+        if let Some(ref version) = self.version {
+            fields.push(("version", "string"));
+            values.push(("version", Value::from(version.as_str())));
+        }
+        if let Some(chain_id) = self.chain_id {
+            fields.push(("chainId", "uint256"));
+            values.push(("chainId", Value::from(chain_id)));
+        }
+        if let Some(address) = self.verifying_contract {
+            fields.push(("verifyingContract", "address"));
+            values.push(("verifyingContract", Value::from(address)));
+        }
+        if let Some(salt) = self.salt {
+            fields.push(("salt", "bytes32"));
+            values.push(("salt", Value::Bytes(salt.to_vec())));
+        }
+        Types::new()
+            .with_struct(DOMAIN_TYPE, &fields)
+            .hash_struct(DOMAIN_TYPE, &Value::from_fields(values))
+            .expect("domain fields have valid types")
+    }
+}
+
+/// A typed message together with its schema and domain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypedData {
+    domain: Domain,
+    types: Types,
+    primary_type: String,
+    message: Value,
+}
+
+impl TypedData {
+    /// Constructs typed data from a message of struct type `primary_type` defined in `types`.
+    pub fn new(domain: Domain, types: Types, primary_type: &str, message: Value) -> Self {
+        TypedData { domain, types, primary_type: primary_type.into(), message }
+    }
+
+    /// Returns the domain.
+    #[inline]
+    pub fn domain(&self) -> &Domain { &self.domain }
+
+    /// Returns the schema.
+    #[inline]
+    pub fn types(&self) -> &Types { &self.types }
+
+    /// Returns the name of the struct type of the message.
+    #[inline]
+    pub fn primary_type(&self) -> &str { &self.primary_type }
+
+    /// Returns the message.
+    #[inline]
+    pub fn message(&self) -> &Value { &self.message }
+
+    /// Computes the digest that is signed.
+    pub fn hash(&self) -> Result<Message, Error> {
+        let mut hasher = Keccak256::new();
+        hasher.update(&[0x19, 0x01]);
+        hasher.update(&self.domain.separator());
+        hasher.update(&self.types.hash_struct(&self.primary_type, &self.message)?);
+        Ok(Message::from_digest(hasher.finalize()))
+    }
+
+    /// Signs the typed data as `eth_signTypedData_v4` does.
+    pub fn sign<C: Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        sk: &SecretKey,
+    ) -> Result<RecoverableSignature, Error> {
+        Ok(secp.sign_ecdsa_recoverable(self.hash()?, sk))
+    }
+
+    /// Recovers the address whose key made `sig` over the typed data.
+    pub fn recover_address<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        sig: &RecoverableSignature,
+    ) -> Result<Address, Error> {
+        super::recover_address(secp, self.hash()?, sig).map_err(|_| Error::InvalidSignature)
+    }
+
+    /// Checks that `sig` is a signature of the typed data by `address`.
+    pub fn verify<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        sig: &RecoverableSignature,
+        address: &Address,
+    ) -> Result<(), Error> {
+        if self.recover_address(secp, sig)? == *address {
+            Ok(())
+        } else {
+            Err(Error::IncorrectSignature)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+
+    fn address(s: &str) -> Value { Value::Address(s.parse().unwrap()) }
+
+    /// The `Mail` example of EIP-712.
+    fn mail() -> TypedData {
+        let domain = Domain::new()
+            .with_name("Ether Mail")
+            .with_version("1")
+            .with_chain_id(1)
+            .with_verifying_contract("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC".parse().unwrap());
+        let types = Types::new()
+            .with_struct("Person", &[("name", "string"), ("wallet", "address")])
+            .with_struct("Mail", &[("from", "Person"), ("to", "Person"), ("contents", "string")]);
+        let message = Value::from_fields([
+            (
+                "from",
+                Value::from_fields([
+                    ("name", "Cow".into()),
+                    ("wallet", address("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826")),
+                ]),
+            ),
+            (
+                "to",
+                Value::from_fields([
+                    ("name", "Bob".into()),
+                    ("wallet", address("0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB")),
+                ]),
+            ),
+            ("contents", "Hello, Bob!".into()),
+        ]);
+        TypedData::new(domain, types, "Mail", message)
+    }
+
+    #[test]
+    fn mail_example() {
+        let data = mail();
+        assert_eq!(
+            data.types().encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            data.types().type_hash("Mail").unwrap(),
+            hex!("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")
+        );
+        assert_eq!(
+            data.domain().separator(),
+            hex!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+        assert_eq!(
+            data.types().hash_struct("Mail", data.message()).unwrap(),
+            hex!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+        assert_eq!(
+            data.hash().unwrap(),
+            Message::from_digest(hex!(
+                "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn mail_signature() {
+        let secp = Secp256k1::new();
+        let data = mail();
+        let sk = SecretKey::from_byte_array(&keccak::hash(b"cow")).unwrap();
+        let cow = "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".parse().unwrap();
+
+        let sig = data.sign(&secp, &sk).unwrap();
+        assert_eq!(
+            sig.to_rsv().unwrap(),
+            hex!("4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c")
+        );
+        assert_eq!(data.recover_address(&secp, &sig), Ok(cow));
+        assert_eq!(data.verify(&secp, &sig, &cow), Ok(()));
+
+        let bob = "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB".parse().unwrap();
+        assert_eq!(data.verify(&secp, &sig, &bob), Err(Error::IncorrectSignature));
+        let other = TypedData::new(
+            Domain::new().with_name("Ether Mail"),
+            data.types().clone(),
+            "Mail",
+            data.message().clone(),
+        );
+        assert_eq!(other.verify(&secp, &sig, &cow), Err(Error::IncorrectSignature));
+    }
+
+    #[test]
+    fn arrays_and_atomic_types() {
+        // Computed with an independent implementation of EIP-712.
+        let types = Types::new()
+            .with_struct("Person", &[("name", "string"), ("wallets", "address[]")])
+            .with_struct(
+                "Mail",
+                &[
+                    ("from", "Person"),
+                    ("to", "Person[]"),
+                    ("contents", "string"),
+                    ("attachment", "bytes"),
+                    ("tag", "bytes4"),
+                    ("nonce", "uint64"),
+                    ("delta", "int32"),
+                    ("urgent", "bool"),
+                    ("grid", "uint8[2][]"),
+                ],
+            )
+            .with_struct("Group", &[("name", "string"), ("members", "Person[]")]);
+        let person = |name: &str, wallets: &[&str]| {
+            Value::from_fields([
+                ("name", name.into()),
+                ("wallets", Value::Array(wallets.iter().map(|w| address(w)).collect())),
+            ])
+        };
+        let row = |a: u64, b: u64| Value::Array(vec![a.into(), b.into()]);
+        let message = Value::from_fields([
+            (
+                "from",
+                person(
+                    "Cow",
+                    &[
+                        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+                        "0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF",
+                    ],
+                ),
+            ),
+            (
+                "to",
+                Value::Array(vec![person(
+                    "Bob",
+                    &[
+                        "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+                        "0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+                        "0xB0B0b0b0b0b0B000000000000000000000000000",
+                    ],
+                )]),
+            ),
+            ("contents", "Hello, Bob!".into()),
+            ("attachment", Value::Bytes(hex!("deadbeef00").to_vec())),
+            ("tag", Value::Bytes(hex!("01020304").to_vec())),
+            ("nonce", u64::MAX.into()),
+            ("delta", (-5i64).into()),
+            ("urgent", true.into()),
+            ("grid", Value::Array(vec![row(1, 2), row(255, 0)])),
+        ]);
+        assert_eq!(
+            types.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person[] to,string contents,bytes attachment,bytes4 tag,\
+             uint64 nonce,int32 delta,bool urgent,uint8[2][] grid)\
+             Person(string name,address[] wallets)"
+        );
+
+        let domain = Domain::new()
+            .with_name("Ether Mail")
+            .with_chain_id(137)
+            .with_salt(hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"));
+        assert_eq!(
+            domain.separator(),
+            hex!("5c337a63ae58900c17fd8d0fcfb3cb0c20253c85e7ddb32800a28dc7908c9cc1")
+        );
+        assert_eq!(
+            types.hash_struct("Mail", &message).unwrap(),
+            hex!("8dd18f1f45a309cf0c9ebb3e6923f456af638ee6b352be902c8eca7d7b9e1810")
+        );
+        let data = TypedData::new(domain, types, "Mail", message);
+        assert_eq!(
+            data.hash().unwrap(),
+            Message::from_digest(hex!(
+                "ac883ece244bc2449747bd4f6f22a38a8351cba3860dd79d4dc76e1a92cf3c5e"
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let hash = |ty: &str, value: Value| {
+            Types::new()
+                .with_struct("S", &[("f", ty)])
+                .hash_struct("S", &Value::from_fields([("f", value)]))
+        };
+        assert!(hash("uint8", 255u64.into()).is_ok());
+        assert_eq!(hash("uint8", 256u64.into()), Err(Error::OutOfRange));
+        assert_eq!(hash("uint8", (-1i64).into()), Err(Error::OutOfRange));
+        assert!(hash("int8", 127u64.into()).is_ok());
+        assert_eq!(hash("int8", 128u64.into()), Err(Error::OutOfRange));
+        assert!(hash("int8", (-128i64).into()).is_ok());
+        assert_eq!(hash("int8", (-129i64).into()), Err(Error::OutOfRange));
+        assert!(hash("int256", Value::Int([0xff; 32])).is_ok());
+        assert_eq!(hash("int256", Value::Uint([0xff; 32])), Err(Error::OutOfRange));
+        assert!(hash("uint256", Value::Uint([0xff; 32])).is_ok());
+        assert_eq!(hash("bytes4", Value::Bytes(vec![0; 3])), Err(Error::OutOfRange));
+        assert_eq!(hash("uint8[2]", Value::Array(vec![1u64.into()])), Err(Error::OutOfRange));
+        assert_eq!(hash("bool", 1u64.into()), Err(Error::TypeMismatch));
+        assert_eq!(hash("string[]", "x".into()), Err(Error::TypeMismatch));
+        for ty in [
+            "uint", "uint7", "uint264", "uint08", "int0", "bytes0", "bytes33", "T", "bool[",
+            "bool[01]", "bool[x]",
+        ] {
+            assert_eq!(hash(ty, true.into()), Err(Error::UnknownType), "{}", ty);
+        }
+
+        let types = Types::new().with_struct("S", &[("a", "bool"), ("b", "bool")]);
+        let value = |fields: &[&str]| Value::from_fields(fields.iter().map(|&f| (f, true.into())));
+        assert!(types.hash_struct("S", &value(&["b", "a"])).is_ok());
+        assert_eq!(types.hash_struct("S", &value(&["a"])), Err(Error::MissingField));
+        assert_eq!(types.hash_struct("S", &value(&["a", "b", "c"])), Err(Error::UnknownField));
+        assert_eq!(types.hash_struct("S", &true.into()), Err(Error::TypeMismatch));
+        assert_eq!(types.hash_struct("T", &value(&[])), Err(Error::UnknownType));
+        assert_eq!(types.encode_type("T"), Err(Error::UnknownType));
+        // Only struct types have an encoding, not atomic types or arrays of structs.
+        assert_eq!(types.encode_type("uint8[]"), Err(Error::UnknownType));
+        assert_eq!(types.encode_type("bool"), Err(Error::UnknownType));
+        assert_eq!(types.type_hash("S[2]"), Err(Error::UnknownType));
+
+        let types = Types::new().with_struct("S", &[("a", "T")]);
+        assert_eq!(types.encode_type("S"), Err(Error::UnknownType));
+
+        // Self-referencing types are encoded once.
+        let types = Types::new().with_struct("Node", &[("value", "uint8"), ("children", "Node[]")]);
+        assert_eq!(types.encode_type("Node").unwrap(), "Node(uint8 value,Node[] children)");
+    }
+}
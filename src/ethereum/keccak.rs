@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! The Keccak-256 hash function used by Ethereum.
+//!
+//! This is the original Keccak submission with a capacity of 512 bits, which differs from the
+//! standardized SHA3-256 only in its padding.
+//!
+
+/// The number of bytes absorbed per permutation.
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the rho step, in the order lanes are visited by the pi step.
+const RHO: [u32; 24] =
+    [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+
+/// The lanes visited by the pi step, starting from lane 1.
+const PI: [usize; 24] =
+    [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+/// An incremental Keccak-256 hasher.
+#[derive(Clone)]
+pub(crate) struct Keccak256 {
+    state: [u64; 25],
+    buf: [u8; RATE],
+    len: usize,
+}
+
+impl Keccak256 {
+    pub(crate) fn new() -> Self { Keccak256 { state: [0; 25], buf: [0; RATE], len: 0 } }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = (RATE - self.len).min(data.len());
+            self.buf[self.len..self.len + n].copy_from_slice(&data[..n]);
+            self.len += n;
+            data = &data[n..];
+            if self.len == RATE {
+                self.absorb();
+            }
+        }
+    }
+
+    pub(crate) fn finalize(mut self) -> [u8; 32] {
+        self.buf[self.len..].fill(0);
+        self.buf[self.len] ^= 0x01;
+        self.buf[RATE - 1] ^= 0x80;
+        self.absorb();
+        let mut out = [0u8; 32];
+        for (chunk, lane) in out.chunks_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&lane.to_le_bytes());
+        }
+        out
+    }
+
+    fn absorb(&mut self) {
+        for (lane, chunk) in self.state.iter_mut().zip(self.buf.chunks(8)) {
+            *lane ^= u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+        }
+        keccak_f(&mut self.state);
+        self.len = 0;
+    }
+}
+
+/// Computes the Keccak-256 hash of `data`.
+pub(crate) fn hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// The Keccak-f[1600] permutation.
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // Theta.
+        let mut c = [0u64; 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            *c = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[5 * y + x] ^= d;
+            }
+        }
+        // Rho and pi.
+        let mut last = a[1];
+        for (&lane, &rotation) in PI.iter().zip(RHO.iter()) {
+            let next = a[lane];
+            a[lane] = last.rotate_left(rotation);
+            last = next;
+        }
+        // Chi.
+        for y in 0..5 {
+            let row = [a[5 * y], a[5 * y + 1], a[5 * y + 2], a[5 * y + 3], a[5 * y + 4]];
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        // Iota.
+        a[0] ^= rc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+
+    #[test]
+    fn vectors() {
+        assert_eq!(
+            hash(b""),
+            hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        // Inputs around the rate boundary, computed with OpenSSL's KECCAK-256.
+        assert_eq!(
+            hash(&[b'a'; 135]),
+            hex!("34367dc248bbd832f4e3e69dfaac2f92638bd0bbd18f2912ba4ef454919cf446")
+        );
+        assert_eq!(
+            hash(&[b'a'; 136]),
+            hex!("a6c4d403279fe3e0af03729caada8374b5ca54d8065329a3ebcaeb4b60aa386e")
+        );
+        assert_eq!(
+            hash(&[b'a'; 200]),
+            hex!("96ea54061def936c4be90b518992fdc6f12f535068a256229aca54267b4d084d")
+        );
+
+        let mut hasher = Keccak256::new();
+        for _ in 0..200 {
+            hasher.update(b"a");
+        }
+        assert_eq!(hasher.finalize(), hash(&[b'a'; 200]));
+    }
+}
@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides Ethereum addresses, [EIP-191] personal messages and the Ethereum encodings of
+//! recoverable signatures.
+//!
+//! An address is the last 20 bytes of the Keccak-256 hash of the uncompressed public key without
+//! its `0x04` prefix. Signatures are encoded either as 65 bytes `r || s || v` with `v = 27 +`
+//! recovery id, or as the 64-byte compact form of [EIP-2098], which stores the recovery id in the
+//! top bit of `s`.
+//!
+//! Typed structured data is hashed and signed as specified by EIP-712 in the `eip712` module,
+//! which requires the `alloc` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "ethereum", feature = "std"))] {
+//! use secp256k1::ethereum::{self, Address};
+//! use secp256k1::ecdsa::RecoverableSignature;
+//! use secp256k1::{Secp256k1, SecretKey};
+//!
+//! let secp = Secp256k1::new();
+//! let sk = SecretKey::from_byte_array(&[0xcd; 32]).expect("valid secret key");
+//! let address = Address::from_public_key(&sk.public_key(&secp));
+//!
+//! let sig = ethereum::sign_message(&secp, b"hello", &sk).to_rsv().unwrap();
+//! let sig = RecoverableSignature::from_rsv(&sig).unwrap();
+//! assert!(ethereum::verify_message(&secp, b"hello", &sig, &address).is_ok());
+//! # }
+//! ```
+//!
+//! [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+//! [EIP-2098]: https://eips.ethereum.org/EIPS/eip-2098
+//!
+
+#[cfg(feature = "alloc")]
+pub mod eip712;
+mod keccak;
+
+use core::{fmt, str};
+
+use crate::ecdsa::{RecoverableSignature, RecoveryId};
+use crate::{from_hex, Message, PublicKey, Secp256k1, SecretKey, Signing, Verification};
+
+/// The prefix of [EIP-191] personal messages, which is followed by the decimal message length.
+///
+/// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+pub const MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// Error parsing an address or decoding or verifying a signature.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The address is not `0x` followed by 40 hex digits.
+    InvalidAddress,
+    /// The address has mixed case but does not match its EIP-55 checksum.
+    InvalidChecksum,
+    /// The `v` byte is not 27 or 28 (or 0 or 1).
+    InvalidV(u8),
+    /// The signature has a recovery id of 2 or 3, which Ethereum cannot represent.
+    UnsupportedRecoveryId,
+    /// The signature is invalid or does not recover a public key.
+    InvalidSignature,
+    /// The signature was made by a different address.
+    IncorrectSignature,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            InvalidAddress => f.write_str("invalid Ethereum address"),
+            InvalidChecksum => f.write_str("invalid EIP-55 address checksum"),
+            InvalidV(v) => write!(f, "invalid signature v value {}", v),
+            UnsupportedRecoveryId => f.write_str("recovery id not supported by Ethereum"),
+            InvalidSignature => f.write_str("invalid signature"),
+            IncorrectSignature => f.write_str("signature was made by a different address"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A 20-byte Ethereum address.
+///
+/// Displays in the mixed-case checksum encoding of [EIP-55]; use `{:x}` for lowercase hex.
+///
+/// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address([u8; 20]);
+
+impl Address {
+    /// Derives the address of `public_key`.
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        let hash = keccak::hash(&public_key.serialize_uncompressed()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+        Address(address)
+    }
+
+    /// Constructs an address from its 20 bytes.
+    #[inline]
+    pub fn from_byte_array(bytes: [u8; 20]) -> Self { Address(bytes) }
+
+    /// Returns the address as a byte array.
+    #[inline]
+    pub fn to_byte_array(self) -> [u8; 20] { self.0 }
+
+    /// Returns a reference to the bytes of the address.
+    #[inline]
+    pub fn as_byte_array(&self) -> &[u8; 20] { &self.0 }
+
+    /// Returns the lowercase hex digits of the address, without `0x`.
+    fn to_lower_hex(self) -> [u8; 40] {
+        let mut hex = [0u8; 40];
+        crate::to_hex(&self.0, &mut hex).expect("buffer is large enough");
+        hex
+    }
+
+    /// Returns the hex digits of the address in EIP-55 mixed case, without `0x`.
+    fn to_checksum_hex(self) -> [u8; 40] {
+        let mut hex = self.to_lower_hex();
+        let hash = keccak::hash(&hex);
+        for (i, c) in hex.iter_mut().enumerate() {
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            if nibble >= 8 {
+                c.make_ascii_uppercase();
+            }
+        }
+        hex
+    }
+}
+
+impl From<PublicKey> for Address {
+    #[inline]
+    fn from(public_key: PublicKey) -> Self { Address::from_public_key(&public_key) }
+}
+
+impl fmt::LowerHex for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        f.write_str(str::from_utf8(&self.to_lower_hex()).expect("hex is ASCII"))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("0x")?;
+        f.write_str(str::from_utf8(&self.to_checksum_hex()).expect("hex is ASCII"))
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "Address({})", self) }
+}
+
+impl str::FromStr for Address {
+    type Err = Error;
+
+    /// Parses `0x` followed by 40 hex digits, checking the EIP-55 checksum if the digits have
+    /// mixed case.
+    fn from_str(s: &str) -> Result<Address, Error> {
+        let hex = s.strip_prefix("0x").ok_or(Error::InvalidAddress)?;
+        let mut address = [0u8; 20];
+        match from_hex(hex, &mut address) {
+            Ok(20) => {}
+            _ => return Err(Error::InvalidAddress),
+        }
+        let address = Address(address);
+        let has_lower = hex.bytes().any(|c| c.is_ascii_lowercase());
+        let has_upper = hex.bytes().any(|c| c.is_ascii_uppercase());
+        if has_lower && has_upper && hex.as_bytes() != address.to_checksum_hex() {
+            return Err(Error::InvalidChecksum);
+        }
+        Ok(address)
+    }
+}
+
+/// Computes the Keccak-256 hash of `data`.
+pub fn keccak256(data: &[u8]) -> [u8; 32] { keccak::hash(data) }
+
+/// Computes the [EIP-191] hash of a personal message, as signed by `personal_sign`.
+///
+/// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+pub fn hash_message(message: &[u8]) -> Message {
+    // The decimal length has at most 20 digits.
+    let mut digits = [0u8; 20];
+    let mut start = digits.len();
+    let mut len = message.len();
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (len % 10) as u8;
+        len /= 10;
+        if len == 0 {
+            break;
+        }
+    }
+
+    let mut hasher = keccak::Keccak256::new();
+    hasher.update(MESSAGE_PREFIX);
+    hasher.update(&digits[start..]);
+    hasher.update(message);
+    Message::from_digest(hasher.finalize())
+}
+
+/// Signs a personal message as `personal_sign` does.
+pub fn sign_message<C: Signing>(
+    secp: &Secp256k1<C>,
+    message: &[u8],
+    sk: &SecretKey,
+) -> RecoverableSignature {
+    secp.sign_ecdsa_recoverable(hash_message(message), sk)
+}
+
+/// Recovers the address whose key made `sig` over `msg`.
+pub fn recover_address<C: Verification>(
+    secp: &Secp256k1<C>,
+    msg: impl Into<Message>,
+    sig: &RecoverableSignature,
+) -> Result<Address, Error> {
+    let pk = secp.recover_ecdsa(msg, sig).map_err(|_| Error::InvalidSignature)?;
+    Ok(Address::from_public_key(&pk))
+}
+
+/// Checks that `sig` is a signature of the personal message `message` by `address`.
+pub fn verify_message<C: Verification>(
+    secp: &Secp256k1<C>,
+    message: &[u8],
+    sig: &RecoverableSignature,
+    address: &Address,
+) -> Result<(), Error> {
+    if recover_address(secp, hash_message(message), sig)? == *address {
+        Ok(())
+    } else {
+        Err(Error::IncorrectSignature)
+    }
+}
+
+impl RecoverableSignature {
+    /// Serializes the signature as 65 bytes `r || s || v`, where `v` is 27 or 28.
+    ///
+    /// Fails if the recovery id is 2 or 3, which happens with negligible probability.
+    pub fn to_rsv(&self) -> Result<[u8; 65], Error> {
+        let (recid, compact) = self.serialize_compact();
+        let mut out = [0u8; 65];
+        out[..64].copy_from_slice(&compact);
+        out[64] = 27 + parity(recid)?;
+        Ok(out)
+    }
+
+    /// Parses a 65-byte `r || s || v` signature, accepting `v` values of 27 and 28 as well as the
+    /// raw recovery ids 0 and 1.
+    pub fn from_rsv(data: &[u8; 65]) -> Result<RecoverableSignature, Error> {
+        let recid = match data[64] {
+            0 | 27 => RecoveryId::Zero,
+            1 | 28 => RecoveryId::One,
+            v => return Err(Error::InvalidV(v)),
+        };
+        RecoverableSignature::from_compact(&data[..64], recid).map_err(|_| Error::InvalidSignature)
+    }
+
+    /// Serializes the signature in the 64-byte compact form of [EIP-2098], `r || yParityAndS`.
+    ///
+    /// A signature with a high S value is normalized first, which flips its recovery id. Fails if
+    /// the recovery id is 2 or 3, which happens with negligible probability.
+    ///
+    /// [EIP-2098]: https://eips.ethereum.org/EIPS/eip-2098
+    pub fn to_eip2098(&self) -> Result<[u8; 64], Error> {
+        let (recid, compact) = self.serialize_compact();
+        let mut y_parity = parity(recid)?;
+        let mut sig = self.to_standard();
+        sig.normalize_s();
+        let mut out = sig.serialize_compact();
+        if out != compact {
+            y_parity ^= 1;
+        }
+        out[32] |= y_parity << 7;
+        Ok(out)
+    }
+
+    /// Parses a 64-byte [EIP-2098] compact signature.
+    ///
+    /// [EIP-2098]: https://eips.ethereum.org/EIPS/eip-2098
+    pub fn from_eip2098(data: &[u8; 64]) -> Result<RecoverableSignature, Error> {
+        let mut compact = *data;
+        let recid = if compact[32] & 0x80 == 0 { RecoveryId::Zero } else { RecoveryId::One };
+        compact[32] &= 0x7f;
+        RecoverableSignature::from_compact(&compact, recid).map_err(|_| Error::InvalidSignature)
+    }
+}
+
+/// Returns the y parity encoded by a recovery id of 0 or 1.
+fn parity(recid: RecoveryId) -> Result<u8, Error> {
+    match recid {
+        RecoveryId::Zero => Ok(0),
+        RecoveryId::One => Ok(1),
+        RecoveryId::Two | RecoveryId::Three => Err(Error::UnsupportedRecoveryId),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+
+    #[test]
+    fn eip55_vectors() {
+        let vectors = [
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+            "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+            "0xde709f2102306220921060314715629080e2fb77",
+            "0x27b1fdb04752bbc536007a920d24acb045561c26",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ];
+        for s in vectors {
+            let address: Address = s.parse().unwrap();
+            assert_eq!(format!("{:#x}", address), s.to_lowercase());
+            assert_eq!(address.to_string().parse(), Ok(address));
+            if s[2..].bytes().any(|c| c.is_ascii_lowercase())
+                && s[2..].bytes().any(|c| c.is_ascii_uppercase())
+            {
+                assert_eq!(address.to_string(), s);
+            }
+        }
+
+        assert_eq!(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".parse::<Address>(),
+            Err(Error::InvalidChecksum)
+        );
+        let invalid = [
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed00",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg",
+        ];
+        for s in invalid {
+            assert_eq!(s.parse::<Address>(), Err(Error::InvalidAddress), "{}", s);
+        }
+    }
+
+    #[test]
+    fn hash_message_vector() {
+        assert_eq!(
+            hash_message(b"Some data"),
+            Message::from_digest(hex!(
+                "1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655"
+            ))
+        );
+        let mut hasher = keccak::Keccak256::new();
+        hasher.update(b"\x19Ethereum Signed Message:\n1234");
+        hasher.update(&[b'a'; 1234]);
+        assert_eq!(hash_message(&[b'a'; 1234]), Message::from_digest(hasher.finalize()));
+        assert_eq!(
+            hash_message(b""),
+            Message::from_digest(keccak256(b"\x19Ethereum Signed Message:\n0"))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", not(secp256k1_fuzz)))]
+    fn sign_and_recover() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_byte_array(&hex!(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        ))
+        .unwrap();
+        let address = Address::from_public_key(&sk.public_key(&secp));
+        assert_eq!(address.to_string(), "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23");
+
+        // The `web3.eth.accounts.sign` example.
+        let sig = sign_message(&secp, b"Some data", &sk);
+        let rsv = hex!("b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c");
+        assert_eq!(sig.to_rsv(), Ok(rsv));
+        assert_eq!(RecoverableSignature::from_rsv(&rsv), Ok(sig));
+        assert_eq!(recover_address(&secp, hash_message(b"Some data"), &sig), Ok(address));
+        assert_eq!(verify_message(&secp, b"Some data", &sig, &address), Ok(()));
+        assert_eq!(
+            verify_message(&secp, b"Other data", &sig, &address),
+            Err(Error::IncorrectSignature)
+        );
+
+        let mut raw = rsv;
+        raw[64] = 1;
+        assert_eq!(RecoverableSignature::from_rsv(&raw), Ok(sig));
+        raw[64] = 29;
+        assert_eq!(RecoverableSignature::from_rsv(&raw), Err(Error::InvalidV(29)));
+        let mut overflow = rsv;
+        overflow[..32].fill(0xff);
+        assert_eq!(RecoverableSignature::from_rsv(&overflow), Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", not(secp256k1_fuzz)))]
+    fn eip2098_vectors() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_byte_array(&hex!(
+            "1234567890123456789012345678901234567890123456789012345678901234"
+        ))
+        .unwrap();
+        let address = Address::from_public_key(&sk.public_key(&secp));
+
+        // The examples of EIP-2098, with `v` of 27 and 28.
+        let vectors = [
+            (
+                &b"Hello World"[..],
+                hex!("68a020a209d3d56c46f38cc50a33f704f4a9a10a59377f8dd762ac66910e9b907e865ad05c4035ab5792787d4a0297a43617ae897930a6fe4d822b8faea520641b"),
+                hex!("68a020a209d3d56c46f38cc50a33f704f4a9a10a59377f8dd762ac66910e9b907e865ad05c4035ab5792787d4a0297a43617ae897930a6fe4d822b8faea52064"),
+            ),
+            (
+                &b"It's a small(er) world"[..],
+                hex!("9328da16089fcba9bececa81663203989f2df5fe1faa6291a45381c81bd17f76139c6d6b623b42da56557e5e734a43dc83345ddfadec52cbe24d0cc64f5507931c"),
+                hex!("9328da16089fcba9bececa81663203989f2df5fe1faa6291a45381c81bd17f76939c6d6b623b42da56557e5e734a43dc83345ddfadec52cbe24d0cc64f550793"),
+            ),
+        ];
+        for (message, rsv, compact) in vectors {
+            let sig = RecoverableSignature::from_rsv(&rsv).unwrap();
+            assert_eq!(sig, sign_message(&secp, message, &sk));
+            assert_eq!(sig.to_eip2098(), Ok(compact));
+            assert_eq!(RecoverableSignature::from_eip2098(&compact), Ok(sig));
+            assert_eq!(verify_message(&secp, message, &sig, &address), Ok(()));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", not(secp256k1_fuzz)))]
+    fn eip2098_high_s() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let msg = hash_message(b"high s");
+        let sig = secp.sign_ecdsa_recoverable(msg, &sk);
+        let (recid, compact) = sig.serialize_compact();
+
+        // Negate s and flip the recovery id, which recovers the same key.
+        let s = SecretKey::from_byte_array(compact[32..].try_into().unwrap()).unwrap().negate();
+        let mut high = compact;
+        high[32..].copy_from_slice(&s.secret_bytes());
+        let flipped = if recid == RecoveryId::Zero { RecoveryId::One } else { RecoveryId::Zero };
+        let high = RecoverableSignature::from_compact(&high, flipped).unwrap();
+        assert_eq!(secp.recover_ecdsa(msg, &high), Ok(sk.public_key(&secp)));
+
+        let encoded = high.to_eip2098().unwrap();
+        assert_eq!(encoded, sig.to_eip2098().unwrap());
+        assert_eq!(RecoverableSignature::from_eip2098(&encoded), Ok(sig));
+    }
+}
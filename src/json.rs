@@ -0,0 +1,349 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Minimal JSON support for the key, signature and event encodings in this crate.
+//!
+//! Parsed texts must consist of a single object. Numbers are kept as their source text, since the
+//! encodings we support only use small integers.
+//!
+
+#[cfg(any(feature = "jws", feature = "nostr"))]
+use core::fmt::Write as _;
+
+use crate::alloc::collections::BTreeSet;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+
+/// Maximum nesting depth of values, to bound recursion on untrusted input.
+const MAX_DEPTH: usize = 32;
+
+/// A parsed JSON value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    /// A number, as it appears in the source text.
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Object),
+}
+
+/// The members of a JSON object, with unique names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Object(Vec<(String, Value)>);
+
+impl Object {
+    /// Parses a JSON text consisting of a single object, rejecting duplicate member names.
+    pub(crate) fn parse(json: &str) -> Result<Object, ()> {
+        let mut parser = Parser { data: json.as_bytes(), pos: 0 };
+        parser.skip_whitespace();
+        if parser.peek() != Some(b'{') {
+            return Err(());
+        }
+        let object = match parser.parse_value(0)? {
+            Value::Object(object) => object,
+            _ => unreachable!("checked for an object above"),
+        };
+        parser.skip_whitespace();
+        if parser.pos != parser.data.len() {
+            return Err(());
+        }
+        Ok(object)
+    }
+
+    /// Returns the value of member `name`, if any.
+    pub(crate) fn get_value(&self, name: &str) -> Option<&Value> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, value)| value)
+    }
+
+    /// Returns the string value of member `name`, failing if it has another type.
+    pub(crate) fn get(&self, name: &str) -> Result<Option<&str>, ()> {
+        match self.get_value(name) {
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(_) => Err(()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Appends `s` to `out` as a JSON string literal.
+#[cfg(any(feature = "jws", feature = "nostr"))]
+pub(crate) fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{0}'..='\u{1f}' =>
+                write!(out, "\\u{:04x}", u32::from(c)).expect("writing to a string cannot fail"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> { self.data.get(self.pos).copied() }
+
+    fn next(&mut self) -> Result<u8, ()> {
+        let c = self.peek().ok_or(())?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), ()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u16, ()> {
+        let mut n = 0;
+        for _ in 0..4 {
+            let digit = char::from(self.next()?).to_digit(16).ok_or(())?;
+            n = n << 4 | digit as u16;
+        }
+        Ok(n)
+    }
+
+    fn parse_string(&mut self) -> Result<String, ()> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next()? {
+                b'"' => break,
+                b'\\' => {
+                    let c = match self.next()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let high = self.parse_hex4()?;
+                            let code = if (0xd800..0xdc00).contains(&high) {
+                                self.expect(b'\\')?;
+                                self.expect(b'u')?;
+                                let low = self.parse_hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(());
+                                }
+                                0x10000
+                                    + ((u32::from(high) - 0xd800) << 10 | (u32::from(low) - 0xdc00))
+                            } else {
+                                u32::from(high)
+                            };
+                            char::from_u32(code).ok_or(())?
+                        }
+                        _ => return Err(()),
+                    };
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                0x00..=0x1f => return Err(()),
+                c => bytes.push(c),
+            }
+        }
+        // The input is a `str` and escapes produce whole characters, so this cannot fail.
+        String::from_utf8(bytes).map_err(|_| ())
+    }
+
+    fn skip_digits(&mut self) -> Result<(), ()> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(());
+        }
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn parse_number(&mut self) -> Result<String, ()> {
+        let start = self.pos;
+        self.eat(b'-');
+        if !self.eat(b'0') {
+            self.skip_digits()?;
+        }
+        if self.eat(b'.') {
+            self.skip_digits()?;
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            self.skip_digits()?;
+        }
+        let number = core::str::from_utf8(&self.data[start..self.pos]).expect("ASCII digits");
+        Ok(number.into())
+    }
+
+    fn parse_literal(&mut self, literal: &[u8], value: Value) -> Result<Value, ()> {
+        if self.data[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(())
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Value, ()> {
+        if depth > MAX_DEPTH {
+            return Err(());
+        }
+        match self.peek().ok_or(())? {
+            b'"' => self.parse_string().map(Value::String),
+            b't' => self.parse_literal(b"true", Value::Bool(true)),
+            b'f' => self.parse_literal(b"false", Value::Bool(false)),
+            b'n' => self.parse_literal(b"null", Value::Null),
+            b'-' | b'0'..=b'9' => self.parse_number().map(Value::Number),
+            b'[' => {
+                self.pos += 1;
+                let mut elements = Vec::new();
+                self.skip_whitespace();
+                if self.eat(b']') {
+                    return Ok(Value::Array(elements));
+                }
+                loop {
+                    self.skip_whitespace();
+                    elements.push(self.parse_value(depth + 1)?);
+                    self.skip_whitespace();
+                    if self.eat(b']') {
+                        return Ok(Value::Array(elements));
+                    }
+                    self.expect(b',')?;
+                }
+            }
+            b'{' => {
+                self.pos += 1;
+                let mut members: Vec<(String, Value)> = Vec::new();
+                let mut names = BTreeSet::new();
+                self.skip_whitespace();
+                if self.eat(b'}') {
+                    return Ok(Value::Object(Object(members)));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let name = self.parse_string()?;
+                    if !names.insert(name.clone()) {
+                        return Err(());
+                    }
+                    self.skip_whitespace();
+                    self.expect(b':')?;
+                    self.skip_whitespace();
+                    members.push((name, self.parse_value(depth + 1)?));
+                    self.skip_whitespace();
+                    if self.eat(b'}') {
+                        return Ok(Value::Object(Object(members)));
+                    }
+                    self.expect(b',')?;
+                }
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let json = r#" {
+            "kty": "EC",
+            "esc\u0061ped": "a\"b\\c\/d\n\ud83d\ude00",
+            "n": -1.5e+3, "t": true, "f": false, "z": null,
+            "a": [1, "x", {"k": [[]]}], "o": {}
+        } "#;
+        let object = Object::parse(json).unwrap();
+        assert_eq!(object.get("kty"), Ok(Some("EC")));
+        assert_eq!(object.get("escaped"), Ok(Some("a\"b\\c/d\n\u{1f600}")));
+        assert_eq!(object.get("missing"), Ok(None));
+        assert!(object.get("n").is_err());
+        assert!(object.get("a").is_err());
+        assert_eq!(object.get_value("n"), Some(&Value::Number("-1.5e+3".into())));
+        assert_eq!(object.get_value("t"), Some(&Value::Bool(true)));
+        assert_eq!(object.get_value("z"), Some(&Value::Null));
+        match object.get_value("a") {
+            Some(Value::Array(elements)) => {
+                assert_eq!(elements[..2], [Value::Number("1".into()), Value::String("x".into())]);
+                assert!(matches!(elements[2], Value::Object(_)));
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+
+        assert!(Object::parse("{}").is_ok());
+    }
+
+    #[test]
+    #[cfg(any(feature = "jws", feature = "nostr"))]
+    fn write_string_round_trip() {
+        let value = "quote \" backslash \\ newline \n nul \u{0} emoji \u{1f600}";
+        let mut json = String::from("{\"k\":");
+        write_string(&mut json, value);
+        json.push('}');
+        assert_eq!(
+            json,
+            "{\"k\":\"quote \\\" backslash \\\\ newline \\n nul \\u0000 emoji \u{1f600}\"}"
+        );
+        assert_eq!(Object::parse(&json).unwrap().get("k"), Ok(Some(value)));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let invalid = [
+            "",
+            "[]",
+            "\"x\"",
+            "{",
+            "{\"a\":1,}",
+            "{\"a\" 1}",
+            "{a:1}",
+            "{\"a\":1}{}",
+            "{\"a\":1,\"a\":2}",
+            "{\"a\":01}",
+            "{\"a\":1.}",
+            "{\"a\":tru}",
+            "{\"a\":\"\\x\"}",
+            "{\"a\":\"\\ud800\"}",
+            "{\"a\":\"\\udc00\"}",
+            "{\"a\":\"\n\"}",
+            "{\"a\":[1,]}",
+            "{\"a\":{\"b\":1,\"b\":2}}",
+        ];
+        for json in invalid {
+            assert!(Object::parse(json).is_err(), "{:?}", json);
+        }
+
+        let deep = format!("{{\"a\":{}{}}}", "[".repeat(100), "]".repeat(100));
+        assert!(Object::parse(&deep).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides JSON Web Key ([RFC 7517]) encodings of keys, using the `secp256k1` curve name
+//! registered by [RFC 8812].
+//!
+//! Public keys are encoded as `{"kty":"EC","crv":"secp256k1","x":..,"y":..}` with the base64url
+//! encoded affine coordinates; secret keys additionally carry the scalar as `"d"`. Parsing ignores
+//! members other than these, and rejects duplicate members and coordinates of the wrong length.
+//!
+//! [RFC 7517]: https://datatracker.ietf.org/doc/html/rfc7517
+//! [RFC 8812]: https://datatracker.ietf.org/doc/html/rfc8812
+//!
+
+use core::fmt;
+
+use hashes::{sha256, Hash};
+
+use crate::alloc::format;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+use crate::base64::{self, Alphabet};
+use crate::json::Object;
+use crate::{constants, Keypair, PublicKey, Secp256k1, SecretKey, Signing};
+
+/// Error decoding a JSON Web Key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input is not a JSON object or a member has the wrong type.
+    InvalidJson,
+    /// A required member is missing.
+    MissingMember(&'static str),
+    /// The key type (`"kty"`) is not `"EC"`.
+    UnsupportedKeyType,
+    /// The curve (`"crv"`) is not `"secp256k1"`.
+    UnsupportedCurve,
+    /// A coordinate or the secret scalar is not base64url of the right length.
+    InvalidEncoding(&'static str),
+    /// The coordinates are not a point on the curve.
+    InvalidPublicKey,
+    /// The secret scalar is zero or not less than the curve order.
+    InvalidSecretKey,
+    /// The coordinates do not belong to the secret key.
+    PublicKeyMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            InvalidJson => f.write_str("invalid JSON object"),
+            MissingMember(name) => write!(f, "missing member {:?}", name),
+            UnsupportedKeyType => f.write_str("key type is not \"EC\""),
+            UnsupportedCurve => f.write_str("curve is not \"secp256k1\""),
+            InvalidEncoding(name) => write!(f, "invalid encoding of member {:?}", name),
+            InvalidPublicKey => f.write_str("invalid public key"),
+            InvalidSecretKey => f.write_str("invalid secret key"),
+            PublicKeyMismatch => f.write_str("public key does not match the secret key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Returns the base64url encoded `x` and `y` coordinates of `pk`.
+fn coordinates(pk: &PublicKey) -> (String, String) {
+    let serialized = pk.serialize_uncompressed();
+    let (x, y) = serialized[1..].split_at(32);
+    (base64::encode(x, Alphabet::UrlSafe, false), base64::encode(y, Alphabet::UrlSafe, false))
+}
+
+/// Encodes the public key `pk` and, if present, the secret key `sk`.
+fn encode(pk: &PublicKey, sk: Option<&SecretKey>) -> String {
+    let (x, y) = coordinates(pk);
+    match sk {
+        Some(sk) => format!(
+            r#"{{"kty":"EC","crv":"secp256k1","x":"{}","y":"{}","d":"{}"}}"#,
+            x,
+            y,
+            base64::encode(&sk.secret_bytes(), Alphabet::UrlSafe, false)
+        ),
+        None => format!(r#"{{"kty":"EC","crv":"secp256k1","x":"{}","y":"{}"}}"#, x, y),
+    }
+}
+
+/// Decodes the base64url value of member `name`, which must be 32 bytes long.
+fn decode_member(jwk: &Object, name: &'static str) -> Result<[u8; 32], Error> {
+    let encoded =
+        jwk.get(name).map_err(|_| Error::InvalidJson)?.ok_or(Error::MissingMember(name))?;
+    let bytes = base64::decode(encoded.as_bytes(), Alphabet::UrlSafe, false)
+        .map_err(|_| Error::InvalidEncoding(name))?;
+    bytes.try_into().map_err(|_: Vec<u8>| Error::InvalidEncoding(name))
+}
+
+/// Parses a JWK, returning its public key and, if present, its secret key.
+fn decode(json: &str) -> Result<(PublicKey, Option<SecretKey>), Error> {
+    let jwk = Object::parse(json).map_err(|_| Error::InvalidJson)?;
+    match jwk.get("kty").map_err(|_| Error::InvalidJson)? {
+        Some("EC") => {}
+        Some(_) => return Err(Error::UnsupportedKeyType),
+        None => return Err(Error::MissingMember("kty")),
+    }
+    match jwk.get("crv").map_err(|_| Error::InvalidJson)? {
+        Some("secp256k1") => {}
+        Some(_) => return Err(Error::UnsupportedCurve),
+        None => return Err(Error::MissingMember("crv")),
+    }
+
+    let mut serialized = [0x04; constants::UNCOMPRESSED_PUBLIC_KEY_SIZE];
+    serialized[1..33].copy_from_slice(&decode_member(&jwk, "x")?);
+    serialized[33..].copy_from_slice(&decode_member(&jwk, "y")?);
+    let pk = PublicKey::from_byte_array_uncompressed(&serialized)
+        .map_err(|_| Error::InvalidPublicKey)?;
+
+    let sk = match jwk.get("d").map_err(|_| Error::InvalidJson)? {
+        Some(_) => Some(
+            SecretKey::from_byte_array(&decode_member(&jwk, "d")?)
+                .map_err(|_| Error::InvalidSecretKey)?,
+        ),
+        None => None,
+    };
+    Ok((pk, sk))
+}
+
+impl PublicKey {
+    /// Serializes the key as a JSON Web Key.
+    pub fn to_jwk(&self) -> String { encode(self, None) }
+
+    /// Parses a JSON Web Key.
+    ///
+    /// A secret key (`"d"`), if present, must be valid but is otherwise ignored.
+    pub fn from_jwk(json: &str) -> Result<PublicKey, Error> { decode(json).map(|(pk, _)| pk) }
+
+    /// Computes the JWK thumbprint of the key as specified in [RFC 7638].
+    ///
+    /// This is the SHA-256 hash of the canonical JSON Web Key. It is commonly used as a key
+    /// identifier, base64url encoded.
+    ///
+    /// [RFC 7638]: https://datatracker.ietf.org/doc/html/rfc7638
+    pub fn jwk_thumbprint(&self) -> [u8; 32] {
+        let (x, y) = coordinates(self);
+        // The required members in lexicographic order, without whitespace.
+        let canonical = format!(r#"{{"crv":"secp256k1","kty":"EC","x":"{}","y":"{}"}}"#, x, y);
+        sha256::Hash::hash(canonical.as_bytes()).to_byte_array()
+    }
+}
+
+impl SecretKey {
+    /// Serializes the key as a JSON Web Key, which includes the public key.
+    pub fn to_jwk<C: Signing>(&self, secp: &Secp256k1<C>) -> String {
+        encode(&self.public_key(secp), Some(self))
+    }
+
+    /// Parses a JSON Web Key holding a secret key.
+    ///
+    /// The public key must be a valid point but is not checked against the secret key; use
+    /// [`Keypair::from_jwk`] to check it.
+    pub fn from_jwk(json: &str) -> Result<SecretKey, Error> {
+        decode(json)?.1.ok_or(Error::MissingMember("d"))
+    }
+}
+
+impl Keypair {
+    /// Serializes the key pair as a JSON Web Key.
+    pub fn to_jwk(&self) -> String { encode(&self.public_key(), Some(&self.secret_key())) }
+
+    /// Parses a JSON Web Key holding a secret key, checking that the public key matches it.
+    pub fn from_jwk<C: Signing>(secp: &Secp256k1<C>, json: &str) -> Result<Keypair, Error> {
+        let (pk, sk) = decode(json)?;
+        let keypair = Keypair::from_secret_key(secp, &sk.ok_or(Error::MissingMember("d"))?);
+        if keypair.public_key() != pk {
+            return Err(Error::PublicKeyMismatch);
+        }
+        Ok(keypair)
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(secp256k1_fuzz))] // The test vectors are real keys, which fuzz-keys can't reproduce.
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+    use crate::der::tests::secret_key;
+
+    // The key sha256("der test"), with coordinates and thumbprint computed independently.
+    const PUBLIC_JWK: &str = r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw","y":"3tXuRY53bZxnTTAgYN_OBX8LctzGhuRqv1Gc_2p7QGk"}"#;
+    const SECRET_JWK: &str = r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw","y":"3tXuRY53bZxnTTAgYN_OBX8LctzGhuRqv1Gc_2p7QGk","d":"Ulfu34CvjGv19ZLn8sCzHFdcpdOwxbPBBXe-JslRzkE"}"#;
+
+    #[test]
+    fn round_trip() {
+        let secp = Secp256k1::new();
+        let sk = secret_key();
+        let keypair = Keypair::from_secret_key(&secp, &sk);
+        let pk = keypair.public_key();
+
+        assert_eq!(pk.to_jwk(), PUBLIC_JWK);
+        assert_eq!(sk.to_jwk(&secp), SECRET_JWK);
+        assert_eq!(keypair.to_jwk(), SECRET_JWK);
+
+        assert_eq!(PublicKey::from_jwk(PUBLIC_JWK), Ok(pk));
+        assert_eq!(PublicKey::from_jwk(SECRET_JWK), Ok(pk));
+        assert_eq!(SecretKey::from_jwk(SECRET_JWK), Ok(sk));
+        assert_eq!(Keypair::from_jwk(&secp, SECRET_JWK), Ok(keypair));
+        assert_eq!(SecretKey::from_jwk(PUBLIC_JWK), Err(Error::MissingMember("d")));
+
+        assert_eq!(
+            pk.jwk_thumbprint(),
+            hex!("d80e6fc6363f1b583849cf0627a18963c839a64a30368e7f3d1574d972ff4c2f")
+        );
+    }
+
+    #[test]
+    fn parse_lenient() {
+        // Whitespace, member order and additional members do not matter.
+        let json = r#"{
+            "use": "sig", "key_ops": ["verify"], "alg": "ES256K", "ext": true,
+            "y": "3tXuRY53bZxnTTAgYN_OBX8LctzGhuRqv1Gc_2p7QGk",
+            "x": "Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw",
+            "crv": "secp256k1",
+            "kty": "EC"
+        }"#;
+        let secp = Secp256k1::new();
+        assert_eq!(PublicKey::from_jwk(json), Ok(secret_key().public_key(&secp)));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let cases = [
+            ("[]", Error::InvalidJson),
+            (r#"{"kty":"EC","kty":"EC"}"#, Error::InvalidJson),
+            (r#"{"kty":1}"#, Error::InvalidJson),
+            (r#"{"crv":"secp256k1"}"#, Error::MissingMember("kty")),
+            (r#"{"kty":"OKP","crv":"Ed25519","x":""}"#, Error::UnsupportedKeyType),
+            (r#"{"kty":"EC","crv":"P-256","x":"","y":""}"#, Error::UnsupportedCurve),
+            (r#"{"kty":"EC","crv":"secp256k1","y":""}"#, Error::MissingMember("x")),
+            (
+                // Padded.
+                r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw=","y":""}"#,
+                Error::InvalidEncoding("x"),
+            ),
+            (
+                // Standard alphabet.
+                r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k/o6plKjTg+aG8VevpaGUTIyEtbw","y":""}"#,
+                Error::InvalidEncoding("x"),
+            ),
+            (
+                // 31 bytes.
+                r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw","y":"3tXuRY53bZxnTTAgYN_OBX8LctzGhuRqv1Gc_2p7QA"}"#,
+                Error::InvalidEncoding("y"),
+            ),
+            (
+                // Not on the curve.
+                r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw","y":"3tXuRY53bZxnTTAgYN_OBX8LctzGhuRqv1Gc_2p7QGg"}"#,
+                Error::InvalidPublicKey,
+            ),
+        ];
+        for (json, error) in cases {
+            assert_eq!(PublicKey::from_jwk(json), Err(error), "{}", json);
+        }
+
+        let zero = r#"{"kty":"EC","crv":"secp256k1","x":"Ryh8ukp9XPzTHx8k_o6plKjTg-aG8VevpaGUTIyEtbw","y":"3tXuRY53bZxnTTAgYN_OBX8LctzGhuRqv1Gc_2p7QGk","d":"AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"}"#;
+        assert_eq!(SecretKey::from_jwk(zero), Err(Error::InvalidSecretKey));
+    }
+
+    #[test]
+    fn public_key_mismatch() {
+        let secp = Secp256k1::new();
+        let other = SecretKey::from_byte_array(&[1; 32]).unwrap();
+        let json = SECRET_JWK.replace(
+            "Ulfu34CvjGv19ZLn8sCzHFdcpdOwxbPBBXe-JslRzkE",
+            &base64::encode(&other.secret_bytes(), Alphabet::UrlSafe, false),
+        );
+        assert_eq!(SecretKey::from_jwk(&json), Ok(other));
+        assert_eq!(Keypair::from_jwk(&secp, &json), Err(Error::PublicKeyMismatch));
+    }
+}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Implements conversions to and from the types of the RustCrypto [`k256`] crate.
+//!
+//! Both libraries accept exactly the same keys, scalars and signatures, so conversions are
+//! lossless. The only fallible ones are from values that `k256` cannot represent: the point at
+//! infinity is not a public key, and ECDSA signatures with a zero `r` or `s` cannot be parsed by
+//! `k256`.
+//!
+
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+
+use crate::{ecdsa, Error, PublicKey, Scalar, SecretKey};
+
+impl From<PublicKey> for k256::PublicKey {
+    fn from(pk: PublicKey) -> Self {
+        k256::PublicKey::from_sec1_bytes(&pk.serialize_uncompressed())
+            .expect("public keys are valid in both libraries")
+    }
+}
+
+impl From<k256::PublicKey> for PublicKey {
+    fn from(pk: k256::PublicKey) -> Self {
+        PublicKey::from_slice(pk.to_encoded_point(false).as_bytes())
+            .expect("public keys are valid in both libraries")
+    }
+}
+
+impl From<PublicKey> for k256::AffinePoint {
+    fn from(pk: PublicKey) -> Self { *k256::PublicKey::from(pk).as_affine() }
+}
+
+/// Fails with [`Error::InvalidPublicKey`] for the point at infinity.
+impl TryFrom<k256::AffinePoint> for PublicKey {
+    type Error = Error;
+    fn try_from(point: k256::AffinePoint) -> Result<Self, Error> {
+        let pk = k256::PublicKey::from_affine(point).map_err(|_| Error::InvalidPublicKey)?;
+        Ok(PublicKey::from(pk))
+    }
+}
+
+impl From<SecretKey> for k256::SecretKey {
+    fn from(sk: SecretKey) -> Self {
+        k256::SecretKey::from_bytes(&sk.secret_bytes().into())
+            .expect("secret keys are valid in both libraries")
+    }
+}
+
+impl From<k256::SecretKey> for SecretKey {
+    fn from(sk: k256::SecretKey) -> Self {
+        SecretKey::from_byte_array(&sk.to_bytes().into())
+            .expect("secret keys are valid in both libraries")
+    }
+}
+
+impl From<SecretKey> for k256::NonZeroScalar {
+    fn from(sk: SecretKey) -> Self { k256::SecretKey::from(sk).to_nonzero_scalar() }
+}
+
+impl From<k256::NonZeroScalar> for SecretKey {
+    fn from(scalar: k256::NonZeroScalar) -> Self { SecretKey::from(k256::SecretKey::from(scalar)) }
+}
+
+impl From<Scalar> for k256::Scalar {
+    fn from(scalar: Scalar) -> Self {
+        Option::from(k256::Scalar::from_repr(scalar.to_be_bytes().into()))
+            .expect("scalars are valid in both libraries")
+    }
+}
+
+impl From<k256::Scalar> for Scalar {
+    fn from(scalar: k256::Scalar) -> Self {
+        Scalar::from_be_bytes(scalar.to_bytes().into())
+            .expect("scalars are valid in both libraries")
+    }
+}
+
+/// Fails with [`Error::InvalidSignature`] if `r` or `s` is zero.
+impl TryFrom<ecdsa::Signature> for k256::ecdsa::Signature {
+    type Error = Error;
+    fn try_from(sig: ecdsa::Signature) -> Result<Self, Error> {
+        k256::ecdsa::Signature::from_slice(&sig.serialize_compact())
+            .map_err(|_| Error::InvalidSignature)
+    }
+}
+
+impl From<k256::ecdsa::Signature> for ecdsa::Signature {
+    fn from(sig: k256::ecdsa::Signature) -> Self {
+        ecdsa::Signature::from_compact(&sig.to_bytes())
+            .expect("signatures of k256 are valid in both libraries")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants;
+
+    #[test]
+    fn secret_key_and_scalar() {
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        assert_eq!(k256::SecretKey::from(sk).to_bytes()[..], [0xcd; 32]);
+        assert_eq!(SecretKey::from(k256::SecretKey::from(sk)), sk);
+        assert_eq!(SecretKey::from(k256::NonZeroScalar::from(sk)), sk);
+
+        let max = SecretKey::from_byte_array(&constants::CURVE_ORDER).err();
+        assert_eq!(max, Some(Error::InvalidSecretKey));
+        for scalar in [Scalar::ZERO, Scalar::ONE, Scalar::MAX] {
+            let k = k256::Scalar::from(scalar);
+            assert_eq!(k.to_bytes()[..], scalar.to_be_bytes());
+            assert_eq!(Scalar::from(k), scalar);
+        }
+        assert_eq!(Scalar::from(-k256::Scalar::ONE), Scalar::MAX);
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn public_key() {
+        let pk = PublicKey::from_slice(&[
+            0x02, 0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce,
+            0x87, 0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81,
+            0x5b, 0x16, 0xf8, 0x17, 0x98,
+        ])
+        .unwrap();
+        assert_eq!(k256::AffinePoint::from(pk), k256::AffinePoint::GENERATOR);
+        assert_eq!(PublicKey::try_from(k256::AffinePoint::GENERATOR), Ok(pk));
+        assert_eq!(PublicKey::from(k256::PublicKey::from(pk)), pk);
+        assert_eq!(PublicKey::try_from(k256::AffinePoint::IDENTITY), Err(Error::InvalidPublicKey));
+    }
+
+    #[test]
+    fn signature() {
+        let mut compact = [0u8; 64];
+        compact[31] = 1;
+        compact[63] = 2;
+        let sig = ecdsa::Signature::from_compact(&compact).unwrap();
+        let k = k256::ecdsa::Signature::try_from(sig).unwrap();
+        assert_eq!(k.to_bytes()[..], compact);
+        assert_eq!(ecdsa::Signature::from(k), sig);
+
+        compact[31] = 0;
+        let zero_r = ecdsa::Signature::from_compact(&compact).unwrap();
+        assert_eq!(k256::ecdsa::Signature::try_from(zero_r), Err(Error::InvalidSignature));
+    }
+}
@@ -16,7 +16,7 @@ use crate::Error::{self, InvalidPublicKey, InvalidPublicKeySum, InvalidSecretKey
 #[cfg(feature = "hashes")]
 #[allow(deprecated)]
 use crate::ThirtyTwoByteHash;
-#[cfg(feature = "global-context")]
+#[cfg(feature = "global-context-no-std")]
 use crate::SECP256K1;
 use crate::{
     constants, ecdsa, from_hex, schnorr, Message, Scalar, Secp256k1, Signing, Verification,
@@ -58,6 +58,26 @@ use crate::{
 pub struct SecretKey([u8; constants::SECRET_KEY_SIZE]);
 impl_display_secret!(SecretKey);
 impl_non_secure_erase!(SecretKey, 0, [1u8; constants::SECRET_KEY_SIZE]);
+impl_subtle!(SecretKey);
+impl_zeroize_guard!(
+    /// A [`SecretKey`] that is erased when dropped.
+    ///
+    /// `SecretKey` is `Copy`, so it cannot erase itself; keeping the key in a guard instead makes
+    /// sure the memory holding it is wiped once it is no longer needed. The key is borrowed for
+    /// signing through `Deref`:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "zeroize", feature = "alloc"))] {
+    /// use secp256k1::{Message, Secp256k1, SecretKey, SecretKeyGuard};
+    ///
+    /// let secp = Secp256k1::new();
+    /// let sk = SecretKeyGuard::new(SecretKey::from_byte_array(&[0xcd; 32]).expect("valid key"));
+    /// let sig = secp.sign_ecdsa(Message::from_digest([0xab; 32]), &sk);
+    /// # }
+    /// ```
+    SecretKeyGuard,
+    SecretKey
+);
 
 impl PartialEq for SecretKey {
     /// This implementation is designed to be constant time to help prevent side channel attacks.
@@ -337,7 +357,7 @@ impl SecretKey {
 
     /// Constructs an ECDSA signature for `msg` using the global [`SECP256K1`] context.
     #[inline]
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
     pub fn sign_ecdsa(&self, msg: impl Into<Message>) -> ecdsa::Signature {
         SECP256K1.sign_ecdsa(msg, self)
     }
@@ -454,7 +474,7 @@ impl PublicKey {
 
     /// Creates a new public key from a [`SecretKey`] and the global [`SECP256K1`] context.
     #[inline]
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
     pub fn from_secret_key_global(sk: &SecretKey) -> PublicKey {
         PublicKey::from_secret_key(SECP256K1, sk)
     }
@@ -825,6 +845,13 @@ impl<'de> serde::Deserialize<'de> for PublicKey {
 /// [`cbor`]: https://docs.rs/cbor
 #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct Keypair(ffi::Keypair);
+impl_zeroize_guard!(
+    /// A [`Keypair`] that is erased when dropped.
+    ///
+    /// See [`SecretKeyGuard`] for details.
+    KeypairGuard,
+    Keypair
+);
 impl_fast_comparisons!(Keypair);
 
 impl Keypair {
@@ -900,7 +927,7 @@ impl Keypair {
     ///
     /// [`Error::InvalidSecretKey`] if corresponding public key for the provided secret key is not even.
     #[inline]
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
     pub fn from_seckey_str_global(s: &str) -> Result<Keypair, Error> {
         Keypair::from_seckey_str(SECP256K1, s)
     }
@@ -933,7 +960,7 @@ impl Keypair {
 
     /// Generates a new random secret key using the global [`SECP256K1`] context.
     #[inline]
-    #[cfg(all(feature = "global-context", feature = "rand"))]
+    #[cfg(all(feature = "global-context-no-std", feature = "rand"))]
     pub fn new_global<R: ::rand::Rng + ?Sized>(rng: &mut R) -> Keypair {
         Keypair::new(SECP256K1, rng)
     }
@@ -1009,7 +1036,7 @@ impl Keypair {
 
     /// Constructs a schnorr signature for `msg` using the global [`SECP256K1`] context.
     #[inline]
-    #[cfg(all(feature = "global-context", feature = "rand", feature = "std"))]
+    #[cfg(all(feature = "global-context-no-std", feature = "rand", feature = "std"))]
     pub fn sign_schnorr(&self, msg: &[u8]) -> schnorr::Signature {
         SECP256K1.sign_schnorr(msg, self)
     }
@@ -1017,7 +1044,7 @@ impl Keypair {
     /// Constructs a schnorr signature without aux rand for `msg` using the global
     /// [`SECP256K1`] context.
     #[inline]
-    #[cfg(all(feature = "global-context", feature = "std"))]
+    #[cfg(all(feature = "global-context-no-std", feature = "std"))]
     pub fn sign_schnorr_no_aux_rand(&self, msg: &[u8]) -> schnorr::Signature {
         SECP256K1.sign_schnorr_no_aux_rand(msg, self)
     }
@@ -1032,6 +1059,36 @@ impl Keypair {
     pub fn non_secure_erase(&mut self) { self.0.non_secure_erase(); }
 }
 
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for Keypair {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.0.as_ref()[..], &other.0.as_ref()[..])
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConditionallySelectable for Keypair {
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+        let mut bytes = *a.0.as_ref();
+        for (byte, other) in bytes.iter_mut().zip(b.0.as_ref().iter()) {
+            subtle::ConditionallySelectable::conditional_assign(byte, other, choice);
+        }
+        // Safe because the bytes are those of either `a` or `b`.
+        Keypair(unsafe { ffi::Keypair::from_array_unchecked(bytes) })
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Keypair {
+    /// Overwrites the key pair using a volatile write, which is not optimized out.
+    ///
+    /// Copies made before the call are not erased; use [`KeypairGuard`] to avoid making them.
+    #[inline]
+    fn zeroize(&mut self) { self.non_secure_erase(); }
+}
+
 impl fmt::Debug for Keypair {
     fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         f.debug_struct("Keypair")
@@ -1061,16 +1118,16 @@ impl<'a> From<&'a Keypair> for PublicKey {
     fn from(pair: &'a Keypair) -> Self { PublicKey::from_keypair(pair) }
 }
 
-#[cfg(any(feature = "global-context", feature = "alloc"))]
+#[cfg(any(feature = "global-context-no-std", feature = "alloc"))]
 impl str::FromStr for Keypair {
     type Err = Error;
 
     #[allow(unused_variables, unreachable_code)] // When built with no default features.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        #[cfg(feature = "global-context")]
+        #[cfg(feature = "global-context-no-std")]
         let ctx = SECP256K1;
 
-        #[cfg(all(not(feature = "global-context"), feature = "alloc"))]
+        #[cfg(all(not(feature = "global-context-no-std"), feature = "alloc"))]
         let ctx = Secp256k1::signing_only();
 
         #[allow(clippy::needless_borrow)]
@@ -1099,7 +1156,7 @@ impl serde::Serialize for Keypair {
 
 #[cfg(feature = "serde")]
 #[allow(unused_variables)] // For `data` under some feature combinations (the unconditional panic below).
-#[cfg(all(feature = "serde", any(feature = "global-context", feature = "alloc")))]
+#[cfg(all(feature = "serde", any(feature = "global-context-no-std", feature = "alloc")))]
 impl<'de> serde::Deserialize<'de> for Keypair {
     fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         if d.is_human_readable() {
@@ -1108,10 +1165,10 @@ impl<'de> serde::Deserialize<'de> for Keypair {
             ))
         } else {
             let visitor = super::serde_util::Tuple32Visitor::new("raw 32 bytes Keypair", |data| {
-                #[cfg(feature = "global-context")]
+                #[cfg(feature = "global-context-no-std")]
                 let ctx = SECP256K1;
 
-                #[cfg(all(not(feature = "global-context"), feature = "alloc"))]
+                #[cfg(all(not(feature = "global-context-no-std"), feature = "alloc"))]
                 let ctx = Secp256k1::signing_only();
 
                 #[allow(clippy::needless_borrow)]
@@ -2253,7 +2310,7 @@ mod test {
     }
 
     #[test]
-    #[cfg(all(feature = "global-context", feature = "serde"))]
+    #[cfg(all(feature = "global-context-no-std", feature = "serde"))]
     fn test_serde_keypair() {
         use serde::{Deserialize, Deserializer, Serialize, Serializer};
         use serde_test::{assert_tokens, Configure, Token};
@@ -2436,7 +2493,7 @@ mod test {
 
     #[test]
     #[cfg(not(secp256k1_fuzz))]
-    #[cfg(all(feature = "global-context", feature = "serde"))]
+    #[cfg(all(feature = "global-context-no-std", feature = "serde"))]
     fn test_serde_x_only_pubkey() {
         use serde_test::{assert_tokens, Configure, Token};
 
@@ -2480,7 +2537,7 @@ mod test {
     }
 
     #[test]
-    #[cfg(all(any(feature = "alloc", feature = "global-context"), feature = "serde"))]
+    #[cfg(all(any(feature = "alloc", feature = "global-context-no-std"), feature = "serde"))]
     fn test_keypair_deserialize_serde() {
         let ctx = crate::Secp256k1::new();
         let sec_key_str = "4242424242424242424242424242424242424242424242424242424242424242";
@@ -2495,6 +2552,90 @@ mod test {
             .collect::<Vec<_>>();
         serde_test::assert_tokens(&keypair.compact(), &tokens);
     }
+
+    #[test]
+    #[cfg(all(feature = "subtle", feature = "alloc"))]
+    fn subtle() {
+        use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+        let secp = Secp256k1::new();
+        let sk1 = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let sk2 = SecretKey::from_byte_array(&[0xab; 32]).unwrap();
+        assert!(bool::from(sk1.ct_eq(&sk1)));
+        assert!(!bool::from(sk1.ct_eq(&sk2)));
+        assert_eq!(SecretKey::conditional_select(&sk1, &sk2, Choice::from(0)), sk1);
+        assert_eq!(SecretKey::conditional_select(&sk1, &sk2, Choice::from(1)), sk2);
+
+        let kp1 = Keypair::from_secret_key(&secp, &sk1);
+        let kp2 = Keypair::from_secret_key(&secp, &sk2);
+        assert!(bool::from(kp1.ct_eq(&kp1)));
+        assert!(!bool::from(kp1.ct_eq(&kp2)));
+        let selected = Keypair::conditional_select(&kp1, &kp2, Choice::from(1));
+        assert_eq!(selected, kp2);
+        assert_eq!(selected.public_key(), PublicKey::from_secret_key(&secp, &sk2));
+
+        let s1 = Scalar::from(sk1);
+        let s2 = Scalar::from(sk2);
+        assert!(!bool::from(s1.ct_eq(&s2)));
+        assert_eq!(Scalar::conditional_select(&s1, &s2, Choice::from(0)), s1);
+
+        let ss1 = crate::ecdh::SharedSecret::new(&PublicKey::from_secret_key(&secp, &sk2), &sk1);
+        let ss2 = crate::ecdh::SharedSecret::from_bytes([0x11; 32]);
+        assert!(bool::from(ss1.ct_eq(&ss1)));
+        assert!(!bool::from(ss1.ct_eq(&ss2)));
+        assert_eq!(crate::ecdh::SharedSecret::conditional_select(&ss1, &ss2, Choice::from(1)), ss2);
+
+        let es1 = crate::ellswift::ElligatorSwiftSharedSecret::from_secret_bytes([0x22; 32]);
+        let es2 = crate::ellswift::ElligatorSwiftSharedSecret::from_secret_bytes([0x33; 32]);
+        assert!(!bool::from(es1.ct_eq(&es2)));
+        assert_eq!(ConditionallySelectable::conditional_select(&es1, &es2, Choice::from(1)), es2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "zeroize", feature = "alloc", not(secp256k1_fuzz)))]
+    fn zeroize() {
+        use zeroize::Zeroize;
+
+        let secp = Secp256k1::new();
+        let mut sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let mut keypair = Keypair::from_secret_key(&secp, &sk);
+        sk.zeroize();
+        keypair.zeroize();
+        // Erased keys are replaced with a valid dummy key rather than an invalid all-zero one.
+        assert_eq!(sk.secret_bytes(), [1; 32]);
+        assert_eq!(keypair.secret_bytes(), [1; 32]);
+        assert_eq!(keypair.public_key(), PublicKey::from_secret_key(&secp, &sk));
+    }
+
+    #[test]
+    #[cfg(all(feature = "zeroize", feature = "alloc"))]
+    fn guards_erase_on_drop() {
+        use core::mem::MaybeUninit;
+
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let msg = crate::Message::from_digest([0xab; 32]);
+
+        let guard = SecretKeyGuard::new(sk);
+        assert_eq!(secp.sign_ecdsa(msg, &guard), secp.sign_ecdsa(msg, &sk));
+        assert_eq!(guard.as_inner(), &sk);
+
+        let mut slot = MaybeUninit::new(guard);
+        let erased = unsafe {
+            slot.as_mut_ptr().drop_in_place();
+            (slot.as_ptr() as *const SecretKey).read()
+        };
+        assert_eq!(erased.secret_bytes(), [1; 32]);
+
+        let guard = KeypairGuard::from(Keypair::from_secret_key(&secp, &sk));
+        assert_eq!(guard.secret_key(), sk);
+        let mut slot = MaybeUninit::new(guard);
+        let erased = unsafe {
+            slot.as_mut_ptr().drop_in_place();
+            (slot.as_ptr() as *const Keypair).read()
+        };
+        assert_eq!(erased.secret_bytes(), [1; 32]);
+    }
 }
 
 #[cfg(bench)]
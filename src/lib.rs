@@ -133,8 +133,33 @@
 //! * `recovery` - enable functions that can compute the public key from signature.
 //! * `lowmemory` - optimize the library for low-memory environments.
 //! * `global-context` - enable use of global secp256k1 context (implies `std`).
+//! * `global-context-no-std` - enable use of global secp256k1 context without requiring `std`;
+//!   the context lives in a static buffer.
+//! * `critical-section` - initialize the `no_std` global context inside a critical section
+//!   instead of spinning on an atomic flag (for targets without atomic compare-and-swap).
+//! * `zeroize` - implements `Zeroize` for secret types and provides wrappers, such as
+//!   [`SecretKeyGuard`], that erase them when dropped.
+//! * `subtle` - implements the `subtle` constant-time comparison and selection traits for secret
+//!   types.
+//! * `signature` - implements the RustCrypto `signature` traits for keys and signatures, using the
+//!   global context (implies `global-context-no-std`).
+//! * `k256` - implements conversions between keys, scalars and ECDSA signatures and their
+//!   RustCrypto `k256` counterparts (requires Rust 1.65).
+//! * `arbitrary` - implements `Arbitrary` for keys, signatures and messages, generating only valid
+//!   values for structure-aware fuzzing, using the global context (implies `alloc` and
+//!   `global-context-no-std`).
+//! * `cose` - enables COSE_Sign1 messages with the `ES256K` algorithm and COSE_Key encodings of
+//!   public keys (implies `alloc` and `hashes`).
+//! * `ethereum` - enables Ethereum addresses, EIP-191 personal messages, EIP-2098 signature
+//!   encodings and, with `alloc`, EIP-712 typed data (implies `recovery`).
+//! * `jwk` - enables JSON Web Key encodings of keys and JWK thumbprints (implies `alloc` and
+//!   `hashes`).
+//! * `jws` - enables JSON Web Signatures with the `ES256K` algorithm (implies `alloc` and
+//!   `hashes`).
+//! * `nostr` - enables Nostr `npub`/`nsec`/`note` encodings, event signing and NIP-44 encryption
+//!   (implies `alloc` and `hashes`).
 //! * `serde` - implements serialization and deserialization for types in this crate using `serde`.
-//!           **Important**: `serde` encoding is **not** the same as consensus encoding!
+//!   **Important**: `serde` encoding is **not** the same as consensus encoding!
 //!
 
 // Coding conventions
@@ -159,29 +184,66 @@ pub extern crate hashes;
 mod macros;
 #[macro_use]
 mod secret;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+#[cfg(feature = "alloc")]
+mod base64;
+mod callback;
 mod context;
+#[cfg(any(feature = "jwk", feature = "jws", feature = "nostr"))]
+mod json;
+#[cfg(feature = "k256")]
+mod k256_interop;
 mod key;
+mod selftest;
 
 pub mod constants;
+#[cfg(feature = "cose")]
+pub mod cose;
+pub mod der;
 pub mod ecdh;
 pub mod ecdsa;
 pub mod ellswift;
+#[cfg(feature = "ethereum")]
+pub mod ethereum;
+#[cfg(feature = "jwk")]
+pub mod jwk;
+#[cfg(feature = "jws")]
+pub mod jws;
+#[cfg(feature = "nostr")]
+pub mod nostr;
+#[cfg(feature = "std")]
+pub mod rerandomize;
 pub mod scalar;
 pub mod schnorr;
 #[cfg(feature = "serde")]
 mod serde_util;
+#[cfg(feature = "signature")]
+mod signature_traits;
+#[cfg(feature = "hashes")]
+pub mod wif;
 
 use core::marker::PhantomData;
-use core::ptr::NonNull;
+use core::ptr::{self, NonNull};
 use core::{fmt, mem, str};
 
-#[cfg(all(feature = "global-context", feature = "std"))]
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
+#[cfg(feature = "global-context-no-std")]
 pub use context::global::{self, SECP256K1};
+#[cfg(feature = "k256")]
+pub use k256;
 #[cfg(feature = "rand")]
 pub use rand;
 pub use secp256k1_sys as ffi;
 #[cfg(feature = "serde")]
 pub use serde;
+#[cfg(feature = "signature")]
+pub use signature;
+#[cfg(feature = "subtle")]
+pub use subtle;
+#[cfg(feature = "zeroize")]
+pub use zeroize;
 
 #[cfg(feature = "alloc")]
 pub use crate::context::{All, SignOnly, VerifyOnly};
@@ -189,10 +251,15 @@ pub use crate::context::{
     AllPreallocated, Context, PreallocatedContext, SignOnlyPreallocated, Signing, Verification,
     VerifyOnlyPreallocated,
 };
-use crate::ffi::types::AlignedType;
+use crate::ffi::types::{c_void, AlignedType};
 use crate::ffi::CPtr;
 pub use crate::key::{InvalidParityValue, Keypair, Parity, PublicKey, SecretKey, XOnlyPublicKey};
+#[cfg(feature = "zeroize")]
+pub use crate::key::{KeypairGuard, SecretKeyGuard};
 pub use crate::scalar::Scalar;
+#[cfg(feature = "alloc")]
+pub use crate::selftest::selftest;
+pub use crate::selftest::{SelfTest, SelfTestReport};
 
 /// Trait describing something that promises to be a 32-byte uniformly random number.
 ///
@@ -307,6 +374,8 @@ pub enum Error {
     InvalidParityValue(key::InvalidParityValue),
     /// Bad EllSwift value
     InvalidEllSwift,
+    /// A user-supplied nonce generation function failed to produce a nonce.
+    NonceGenerationFailed,
 }
 
 impl fmt::Display for Error {
@@ -328,6 +397,7 @@ impl fmt::Display for Error {
             ),
             InvalidParityValue(e) => write_err!(f, "couldn't create parity"; e),
             InvalidEllSwift => f.write_str("malformed EllSwift value"),
+            NonceGenerationFailed => f.write_str("nonce generation function failed"),
         }
     }
 }
@@ -348,6 +418,7 @@ impl std::error::Error for Error {
             Error::InvalidPublicKeySum => None,
             Error::InvalidParityValue(error) => Some(error),
             Error::InvalidEllSwift => None,
+            Error::NonceGenerationFailed => None,
         }
     }
 }
@@ -405,6 +476,7 @@ impl<C: Context> Secp256k1<C> {
     ///
     /// Requires compilation with "rand" feature. See comment by Gregory Maxwell in
     /// [libsecp256k1](https://github.com/bitcoin-core/secp256k1/commit/d2275795ff22a6f4738869f5528fbbb61738aa48).
+    /// To re-randomize periodically see [`rerandomize::RerandomizingContext`].
     #[cfg(feature = "rand")]
     pub fn randomize<R: rand::Rng + ?Sized>(&mut self, rng: &mut R) {
         let mut seed = [0u8; 32];
@@ -429,6 +501,85 @@ impl<C: Context> Secp256k1<C> {
             assert_eq!(err, 1);
         }
     }
+
+    /// Sets a callback to be called when an illegal argument is passed to libsecp256k1.
+    ///
+    /// The type system of this crate prevents illegal arguments from reaching the library, so
+    /// the callback only triggers if the context is used directly through the [`ffi`] bindings.
+    /// When it triggers, the called function returns without crashing, though its return value
+    /// and output arguments are undefined. By default a panic is raised; a custom callback can
+    /// log the message or record the failure instead.
+    ///
+    /// The callback is shared with clones of this context. It must not panic: since there is no
+    /// Rust caller to propagate a panic to, doing so aborts the process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use core::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// use secp256k1::{ffi, Secp256k1};
+    ///
+    /// static MISUSED: AtomicBool = AtomicBool::new(false);
+    /// static CALLBACK: fn(&str) = |_msg| MISUSED.store(true, Ordering::SeqCst);
+    ///
+    /// let mut secp = Secp256k1::new();
+    /// secp.set_illegal_callback(&CALLBACK);
+    ///
+    /// let ret = unsafe { ffi::secp256k1_ec_seckey_verify(secp.ctx().as_ptr(), core::ptr::null()) };
+    /// assert_eq!(ret, 0);
+    /// assert!(MISUSED.load(Ordering::SeqCst));
+    /// # }
+    /// ```
+    pub fn set_illegal_callback<F>(&mut self, callback: &'static F)
+    where
+        F: Fn(&str) + Sync + 'static,
+    {
+        unsafe {
+            ffi::secp256k1_context_set_illegal_callback(
+                self.ctx,
+                Some(callback::message_callback_trampoline::<F>),
+                callback as *const F as *const c_void,
+            );
+        }
+    }
+
+    /// Restores the default illegal argument callback, which panics.
+    pub fn reset_illegal_callback(&mut self) {
+        unsafe {
+            ffi::secp256k1_context_set_illegal_callback(self.ctx, None, ptr::null());
+        }
+    }
+
+    /// Sets a callback to be called when an internal consistency check in libsecp256k1 fails.
+    ///
+    /// This can only trigger in case of a hardware failure, miscompilation, memory corruption,
+    /// serious bug in the library, or other error that can otherwise result in undefined
+    /// behavior. After the callback returns anything may happen, including crashing, so it is
+    /// only useful for logging. By default a panic is raised.
+    ///
+    /// The callback is shared with clones of this context. It must not panic: since there is no
+    /// Rust caller to propagate a panic to, doing so aborts the process.
+    pub fn set_error_callback<F>(&mut self, callback: &'static F)
+    where
+        F: Fn(&str) + Sync + 'static,
+    {
+        unsafe {
+            ffi::secp256k1_context_set_error_callback(
+                self.ctx,
+                Some(callback::message_callback_trampoline::<F>),
+                callback as *const F as *const c_void,
+            );
+        }
+    }
+
+    /// Restores the default internal error callback, which panics.
+    pub fn reset_error_callback(&mut self) {
+        unsafe {
+            ffi::secp256k1_context_set_error_callback(self.ctx, None, ptr::null());
+        }
+    }
 }
 
 impl<C: Signing> Secp256k1<C> {
@@ -448,7 +599,7 @@ impl<C: Signing> Secp256k1<C> {
 
 /// Generates a random keypair using the global [`SECP256K1`] context.
 #[inline]
-#[cfg(all(feature = "global-context", feature = "rand"))]
+#[cfg(all(feature = "global-context-no-std", feature = "rand"))]
 pub fn generate_keypair<R: rand::Rng + ?Sized>(rng: &mut R) -> (key::SecretKey, key::PublicKey) {
     SECP256K1.generate_keypair(rng)
 }
@@ -499,7 +650,7 @@ fn to_hex<'a>(src: &[u8], target: &'a mut [u8]) -> Result<&'a str, ()> {
     }
     let result = &target[..hex_len];
     debug_assert!(str::from_utf8(result).is_ok());
-    return unsafe { Ok(str::from_utf8_unchecked(result)) };
+    unsafe { Ok(str::from_utf8_unchecked(result)) }
 }
 
 #[cfg(feature = "rand")]
@@ -591,6 +742,48 @@ mod tests {
         pk.serialize();
     }
 
+    #[test]
+    #[cfg(not(secp256k1_fuzz))] // fuzz-sys functions do not check their arguments
+    #[cfg(feature = "alloc")]
+    fn illegal_callback() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static CALLBACK: fn(&str) = |msg| {
+            assert!(msg.contains("seckey != NULL"), "unexpected message: {}", msg);
+            CALLS.fetch_add(1, Ordering::SeqCst);
+        };
+
+        let mut secp = Secp256k1::new();
+        secp.set_illegal_callback(&CALLBACK);
+        // Clones share the callback.
+        let clone = secp.clone();
+        for ctx in [secp.ctx(), clone.ctx()] {
+            let ret = unsafe { ffi::secp256k1_ec_seckey_verify(ctx.as_ptr(), ptr::null()) };
+            assert_eq!(ret, 0);
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+
+        // Legal calls do not trigger the callback.
+        let sk = SecretKey::from_byte_array(&[1; 32]).unwrap();
+        let _ = PublicKey::from_secret_key(&secp, &sk);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+
+        secp.reset_illegal_callback();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn error_callback() {
+        static CALLBACK: fn(&str) = |msg| panic!("unexpected internal error: {}", msg);
+
+        let mut secp = Secp256k1::new();
+        secp.set_error_callback(&CALLBACK);
+        let sk = SecretKey::from_byte_array(&[1; 32]).unwrap();
+        let _ = PublicKey::from_secret_key(&secp, &sk);
+        secp.reset_error_callback();
+    }
+
     #[test]
     #[cfg(all(feature = "rand", feature = "std"))]
     fn test_preallocation() {
@@ -954,6 +1147,47 @@ mod tests {
         assert_eq!(expected_sig, sig);
     }
 
+    #[test]
+    #[cfg(secp256k1_fuzz)] // pins invariants of the fuzz stubs themselves
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    fn test_fuzz_stubs_consistent() {
+        use crate::ellswift::{ElligatorSwift, Party};
+
+        let secp = Secp256k1::new();
+        let (mut even, mut odd) = (false, false);
+        for byte in [0x01, 0x42, 0x7f, 0x80, 0xa5, 0xfe] {
+            let sk = SecretKey::from_slice(&[byte; 32]).unwrap();
+            let pk = PublicKey::from_secret_key(&secp, &sk);
+            let parity = match pk.serialize()[0] {
+                0x02 => Parity::Even,
+                0x03 => Parity::Odd,
+                prefix => panic!("unexpected prefix {:#04x}", prefix),
+            };
+            even |= parity == Parity::Even;
+            odd |= parity == Parity::Odd;
+
+            let uncompressed = pk.serialize_uncompressed();
+            assert_eq!(PublicKey::from_slice(&uncompressed).unwrap(), pk);
+
+            let (xonly, xonly_parity) = pk.x_only_public_key();
+            assert_eq!(xonly_parity, parity);
+            assert_eq!(XOnlyPublicKey::from_slice(&pk.serialize()[1..]).unwrap(), xonly);
+            assert_eq!(PublicKey::from_x_only_public_key(xonly, parity), pk);
+
+            let keypair = Keypair::from_secret_key(&secp, &sk);
+            assert_eq!(keypair.x_only_public_key(), (xonly, parity));
+
+            let other = SecretKey::from_slice(&[byte.wrapping_add(0x40); 32]).unwrap();
+            let ell_a = ElligatorSwift::from_seckey(&secp, sk, None);
+            let ell_b = ElligatorSwift::from_seckey(&secp, other, None);
+            assert_eq!(
+                ElligatorSwift::shared_secret(ell_a, ell_b, sk, Party::Initiator, None),
+                ElligatorSwift::shared_secret(ell_a, ell_b, other, Party::Responder, None),
+            );
+        }
+        assert!(even && odd, "the keys should cover both parities");
+    }
+
     #[cfg(feature = "serde")]
     #[cfg(not(secp256k1_fuzz))] // fixed sig vectors can't work with fuzz-sigs
     #[cfg(any(feature = "alloc", feature = "std"))]
@@ -986,7 +1220,15 @@ mod tests {
         assert_tokens(&sig.readable(), &[Token::String(SIG_STR)]);
     }
 
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
+    #[test]
+    fn test_global_context_entropy_hook_too_late() {
+        let sk = SecretKey::from_slice(&[1; 32]).unwrap();
+        let _ = PublicKey::from_secret_key_global(&sk);
+        assert!(!global::set_entropy_hook(|| [1; 32]));
+    }
+
+    #[cfg(feature = "global-context-no-std")]
     #[test]
     fn test_global_context() {
         use crate::SECP256K1;
@@ -1001,6 +1243,10 @@ mod tests {
         // Check usage as self
         let sig = SECP256K1.sign_ecdsa(msg, &sk);
         assert!(SECP256K1.verify_ecdsa(msg, &sig, &pk).is_ok());
+
+        // With `std` the global context can be passed where a `Secp256k1<All>` is expected.
+        #[cfg(feature = "std")]
+        let _: &Secp256k1<All> = SECP256K1;
     }
 }
 
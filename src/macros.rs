@@ -67,6 +67,103 @@ macro_rules! impl_non_secure_erase {
                 secp256k1_sys::non_secure_erase_impl(&mut self.$target, $value);
             }
         }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for $thing {
+            /// Overwrites the value using a volatile write, which is not optimized out.
+            ///
+            /// Copies made before the call are not erased; see [`zeroize::ZeroizeOnDrop`] wrappers
+            /// such as [`crate::SecretKeyGuard`] to avoid making them.
+            #[inline]
+            fn zeroize(&mut self) { self.non_secure_erase(); }
+        }
+    };
+}
+
+/// Implements the `subtle` constant-time traits for a type wrapping a byte array.
+///
+/// Only valid if every byte-wise combination of two valid values is itself valid, which holds for
+/// the secret types because `conditional_select` always returns one of its inputs.
+macro_rules! impl_subtle {
+    ($thing:ident) => {
+        #[cfg(feature = "subtle")]
+        impl subtle::ConstantTimeEq for $thing {
+            #[inline]
+            fn ct_eq(&self, other: &Self) -> subtle::Choice {
+                subtle::ConstantTimeEq::ct_eq(&self.0[..], &other.0[..])
+            }
+        }
+
+        #[cfg(feature = "subtle")]
+        impl subtle::ConditionallySelectable for $thing {
+            #[inline]
+            fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+                let mut bytes = a.0;
+                for (byte, other) in bytes.iter_mut().zip(b.0.iter()) {
+                    subtle::ConditionallySelectable::conditional_assign(byte, other, choice);
+                }
+                $thing(bytes)
+            }
+        }
+    };
+}
+
+/// Implements a wrapper around a secret type that erases the secret when dropped.
+macro_rules! impl_zeroize_guard {
+    ($(#[$attr:meta])* $guard:ident, $inner:ty) => {
+        $(#[$attr])*
+        #[cfg(feature = "zeroize")]
+        #[derive(Clone)]
+        #[repr(transparent)]
+        pub struct $guard($inner);
+
+        #[cfg(feature = "zeroize")]
+        impl $guard {
+            /// Wraps `secret`, which is erased when the guard is dropped.
+            ///
+            /// `secret` is moved into the guard; any other copies of it are not erased.
+            #[inline]
+            pub fn new(secret: $inner) -> Self { $guard(secret) }
+
+            /// Returns a reference to the guarded secret.
+            #[inline]
+            pub fn as_inner(&self) -> &$inner { &self.0 }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl From<$inner> for $guard {
+            #[inline]
+            fn from(secret: $inner) -> Self { $guard::new(secret) }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl core::ops::Deref for $guard {
+            type Target = $inner;
+
+            #[inline]
+            fn deref(&self) -> &$inner { &self.0 }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl AsRef<$inner> for $guard {
+            #[inline]
+            fn as_ref(&self) -> &$inner { &self.0 }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for $guard {
+            fn drop(&mut self) { zeroize::Zeroize::zeroize(&mut self.0); }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for $guard {}
+
+        #[cfg(feature = "zeroize")]
+        impl core::fmt::Debug for $guard {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.debug_tuple(stringify!($guard)).field(&self.0).finish()
+            }
+        }
     };
 }
 
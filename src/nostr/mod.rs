@@ -0,0 +1,762 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides the key encodings of [NIP-19] and the signed events of [NIP-01] used by Nostr.
+//!
+//! Public keys, secret keys and event ids are encoded in bech32 with the human-readable prefixes
+//! `npub`, `nsec` and `note` respectively. An event id is the SHA-256 hash of the canonical JSON
+//! serialization of the event, and is signed with a BIP 340 Schnorr signature by the author's
+//! x-only public key.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "nostr", feature = "std"))] {
+//! use secp256k1::nostr::{Event, UnsignedEvent};
+//! use secp256k1::{Keypair, Secp256k1, SecretKey};
+//!
+//! let secp = Secp256k1::new();
+//! let sk = SecretKey::from_byte_array(&[0xcd; 32]).expect("valid secret key");
+//! let keypair = Keypair::from_secret_key(&secp, &sk);
+//!
+//! let event = UnsignedEvent::new(1700000000, 1, "Hello, nostr!")
+//!     .with_tag(&["t", "greeting"])
+//!     .sign_with_aux_rand(&secp, &keypair, &[0; 32]);
+//! let event = Event::from_json(&event.to_json()).unwrap();
+//! assert!(event.verify(&secp).is_ok());
+//! assert!(event.pubkey().to_npub().starts_with("npub1"));
+//! # }
+//! ```
+//!
+//! [NIP-01]: https://github.com/nostr-protocol/nips/blob/master/01.md
+//! [NIP-19]: https://github.com/nostr-protocol/nips/blob/master/19.md
+//!
+
+pub mod nip44;
+
+use core::{fmt, str};
+
+use hashes::{sha256, Hash};
+
+use crate::alloc::string::{String, ToString};
+use crate::alloc::vec::Vec;
+use crate::json::{self, Object, Value};
+use crate::{
+    constants, from_hex, schnorr, to_hex, Keypair, Secp256k1, SecretKey, Signing, Verification,
+    XOnlyPublicKey,
+};
+
+/// The bech32 prefix of public keys.
+pub const NPUB_PREFIX: &str = "npub";
+
+/// The bech32 prefix of secret keys.
+pub const NSEC_PREFIX: &str = "nsec";
+
+/// The bech32 prefix of event ids.
+pub const NOTE_PREFIX: &str = "note";
+
+/// The bech32 alphabet.
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The number of 5-bit values encoding a 32-byte payload.
+const DATA_LEN: usize = 52;
+
+/// The number of 5-bit values in a bech32 checksum.
+const CHECKSUM_LEN: usize = 6;
+
+/// The maximum length of a bech32 string.
+const MAX_LEN: usize = 90;
+
+/// Error decoding a NIP-19 string or a NIP-01 event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The string is not valid bech32, for example because of a bad character or checksum.
+    InvalidBech32,
+    /// The human-readable prefix is not the one expected for the value.
+    UnexpectedPrefix,
+    /// The payload is not 32 bytes long.
+    InvalidLength,
+    /// The payload is not a valid public key.
+    InvalidPublicKey,
+    /// The payload is not a valid secret key.
+    InvalidSecretKey,
+    /// The event is not a JSON object.
+    InvalidJson,
+    /// The named event member is missing or has an invalid value.
+    InvalidMember(&'static str),
+    /// The event id is not the hash of the event.
+    IdMismatch,
+    /// The signature does not verify for the event id and public key.
+    IncorrectSignature,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            InvalidBech32 => f.write_str("invalid bech32 string"),
+            UnexpectedPrefix => f.write_str("unexpected bech32 prefix"),
+            InvalidLength => f.write_str("invalid bech32 payload length (expected 32 bytes)"),
+            InvalidPublicKey => f.write_str("invalid public key"),
+            InvalidSecretKey => f.write_str("invalid secret key"),
+            InvalidJson => f.write_str("event is not a JSON object"),
+            InvalidMember(name) => write!(f, "event member `{}` is missing or invalid", name),
+            IdMismatch => f.write_str("event id does not match the event"),
+            IncorrectSignature => f.write_str("incorrect event signature"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// The id of an event: the SHA-256 hash of its canonical serialization.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventId([u8; 32]);
+
+impl EventId {
+    /// Constructs an event id from its 32 bytes.
+    #[inline]
+    pub fn from_byte_array(bytes: [u8; 32]) -> Self { EventId(bytes) }
+
+    /// Returns the event id as a byte array.
+    #[inline]
+    pub fn to_byte_array(self) -> [u8; 32] { self.0 }
+
+    /// Returns a reference to the bytes of the event id.
+    #[inline]
+    pub fn as_byte_array(&self) -> &[u8; 32] { &self.0 }
+
+    /// Encodes the event id in bech32 with the `note` prefix.
+    pub fn to_note(&self) -> String { bech32_encode(NOTE_PREFIX, &self.0) }
+
+    /// Decodes an event id from bech32 with the `note` prefix.
+    pub fn from_note(s: &str) -> Result<EventId, Error> {
+        bech32_decode(NOTE_PREFIX, s).map(EventId)
+    }
+}
+
+impl fmt::LowerHex for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::LowerHex::fmt(self, f) }
+}
+
+impl fmt::Debug for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "EventId({:x})", self) }
+}
+
+impl str::FromStr for EventId {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<EventId, Error> {
+        parse_hex(s).map(EventId).ok_or(Error::InvalidMember("id"))
+    }
+}
+
+/// An event that has not been signed yet.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnsignedEvent {
+    created_at: u64,
+    kind: u16,
+    tags: Vec<Vec<String>>,
+    content: String,
+}
+
+impl UnsignedEvent {
+    /// Constructs an event without tags.
+    ///
+    /// `created_at` is the creation time in seconds since the Unix epoch.
+    pub fn new(created_at: u64, kind: u16, content: &str) -> Self {
+        UnsignedEvent { created_at, kind, tags: Vec::new(), content: content.into() }
+    }
+
+    /// Appends a tag, given as its name followed by its values.
+    pub fn with_tag(mut self, tag: &[&str]) -> Self {
+        self.tags.push(tag.iter().map(|&s| s.into()).collect());
+        self
+    }
+
+    /// Returns the creation time in seconds since the Unix epoch.
+    #[inline]
+    pub fn created_at(&self) -> u64 { self.created_at }
+
+    /// Returns the kind of the event.
+    #[inline]
+    pub fn kind(&self) -> u16 { self.kind }
+
+    /// Returns the tags of the event.
+    #[inline]
+    pub fn tags(&self) -> &[Vec<String>] { &self.tags }
+
+    /// Returns the content of the event.
+    #[inline]
+    pub fn content(&self) -> &str { &self.content }
+
+    /// Computes the id the event has when authored by `pubkey`.
+    pub fn id(&self, pubkey: &XOnlyPublicKey) -> EventId {
+        event_id(pubkey, self.created_at, self.kind, &self.tags, &self.content)
+    }
+
+    /// Signs the event with `keypair`, using the given auxiliary random data.
+    pub fn sign_with_aux_rand<C: Signing>(
+        self,
+        secp: &Secp256k1<C>,
+        keypair: &Keypair,
+        aux_rand: &[u8; 32],
+    ) -> Event {
+        let pubkey = keypair.x_only_public_key().0;
+        let id = self.id(&pubkey);
+        let sig = secp.sign_schnorr_with_aux_rand(&id.0, keypair, aux_rand);
+        Event {
+            id,
+            pubkey,
+            created_at: self.created_at,
+            kind: self.kind,
+            tags: self.tags,
+            content: self.content,
+            sig,
+        }
+    }
+
+    /// Signs the event with `keypair`, drawing auxiliary random data from the thread-local
+    /// random number generator.
+    #[cfg(all(feature = "rand", feature = "std"))]
+    pub fn sign<C: Signing>(self, secp: &Secp256k1<C>, keypair: &Keypair) -> Event {
+        self.sign_with_aux_rand(secp, keypair, &crate::random_32_bytes(&mut rand::thread_rng()))
+    }
+}
+
+/// A signed event.
+///
+/// Events decoded with [`Event::from_json`] are not checked until [`Event::verify`] is called.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Event {
+    id: EventId,
+    pubkey: XOnlyPublicKey,
+    created_at: u64,
+    kind: u16,
+    tags: Vec<Vec<String>>,
+    content: String,
+    sig: schnorr::Signature,
+}
+
+impl Event {
+    /// Returns the id of the event.
+    #[inline]
+    pub fn id(&self) -> EventId { self.id }
+
+    /// Returns the public key of the author.
+    #[inline]
+    pub fn pubkey(&self) -> XOnlyPublicKey { self.pubkey }
+
+    /// Returns the creation time in seconds since the Unix epoch.
+    #[inline]
+    pub fn created_at(&self) -> u64 { self.created_at }
+
+    /// Returns the kind of the event.
+    #[inline]
+    pub fn kind(&self) -> u16 { self.kind }
+
+    /// Returns the tags of the event.
+    #[inline]
+    pub fn tags(&self) -> &[Vec<String>] { &self.tags }
+
+    /// Returns the content of the event.
+    #[inline]
+    pub fn content(&self) -> &str { &self.content }
+
+    /// Returns the signature of the event id.
+    #[inline]
+    pub fn signature(&self) -> schnorr::Signature { self.sig }
+
+    /// Checks that the id is the hash of the event and that the signature is valid.
+    pub fn verify<C: Verification>(&self, secp: &Secp256k1<C>) -> Result<(), Error> {
+        let id = event_id(&self.pubkey, self.created_at, self.kind, &self.tags, &self.content);
+        if id != self.id {
+            return Err(Error::IdMismatch);
+        }
+        secp.verify_schnorr(&self.sig, &self.id.0, &self.pubkey)
+            .map_err(|_| Error::IncorrectSignature)
+    }
+
+    /// Serializes the event as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut buf = [0u8; constants::SCHNORR_SIGNATURE_SIZE * 2];
+        let mut out = String::new();
+        out.push_str("{\"id\":\"");
+        out.push_str(to_hex(&self.id.0, &mut buf).expect("buffer is large enough"));
+        out.push_str("\",\"pubkey\":\"");
+        out.push_str(to_hex(&self.pubkey.serialize(), &mut buf).expect("buffer is large enough"));
+        out.push_str("\",\"created_at\":");
+        out.push_str(&self.created_at.to_string());
+        out.push_str(",\"kind\":");
+        out.push_str(&self.kind.to_string());
+        out.push_str(",\"tags\":");
+        write_tags(&mut out, &self.tags, json::write_string);
+        out.push_str(",\"content\":");
+        json::write_string(&mut out, &self.content);
+        out.push_str(",\"sig\":\"");
+        out.push_str(to_hex(self.sig.as_byte_array(), &mut buf).expect("buffer is large enough"));
+        out.push_str("\"}");
+        out
+    }
+
+    /// Parses an event from a JSON object, ignoring unknown members.
+    ///
+    /// The id and signature are not checked; call [`Event::verify`] to do so.
+    pub fn from_json(json: &str) -> Result<Event, Error> {
+        let object = Object::parse(json).map_err(|_| Error::InvalidJson)?;
+        let string = |name| match object.get(name) {
+            Ok(Some(s)) => Ok(s),
+            _ => Err(Error::InvalidMember(name)),
+        };
+        let number = |name| match object.get_value(name) {
+            Some(Value::Number(n)) => Ok(n.as_str()),
+            _ => Err(Error::InvalidMember(name)),
+        };
+
+        let id = string("id")?.parse()?;
+        let pubkey = parse_hex(string("pubkey")?)
+            .and_then(|bytes| XOnlyPublicKey::from_byte_array(&bytes).ok())
+            .ok_or(Error::InvalidMember("pubkey"))?;
+        let created_at =
+            number("created_at")?.parse().map_err(|_| Error::InvalidMember("created_at"))?;
+        let kind = number("kind")?.parse().map_err(|_| Error::InvalidMember("kind"))?;
+        let tags = match object.get_value("tags") {
+            Some(Value::Array(tags)) => tags
+                .iter()
+                .map(|tag| match tag {
+                    Value::Array(values) => values
+                        .iter()
+                        .map(|value| match value {
+                            Value::String(s) => Ok(s.clone()),
+                            _ => Err(Error::InvalidMember("tags")),
+                        })
+                        .collect(),
+                    _ => Err(Error::InvalidMember("tags")),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => return Err(Error::InvalidMember("tags")),
+        };
+        let content = string("content")?.into();
+        let sig = parse_hex(string("sig")?)
+            .map(schnorr::Signature::from_byte_array)
+            .ok_or(Error::InvalidMember("sig"))?;
+        Ok(Event { id, pubkey, created_at, kind, tags, content, sig })
+    }
+}
+
+impl XOnlyPublicKey {
+    /// Encodes the public key in bech32 with the `npub` prefix.
+    pub fn to_npub(&self) -> String { bech32_encode(NPUB_PREFIX, &self.serialize()) }
+
+    /// Decodes a public key from bech32 with the `npub` prefix.
+    pub fn from_npub(s: &str) -> Result<XOnlyPublicKey, Error> {
+        let bytes = bech32_decode(NPUB_PREFIX, s)?;
+        XOnlyPublicKey::from_byte_array(&bytes).map_err(|_| Error::InvalidPublicKey)
+    }
+}
+
+impl SecretKey {
+    /// Encodes the secret key in bech32 with the `nsec` prefix.
+    pub fn to_nsec(&self) -> String { bech32_encode(NSEC_PREFIX, &self.secret_bytes()) }
+
+    /// Decodes a secret key from bech32 with the `nsec` prefix.
+    pub fn from_nsec(s: &str) -> Result<SecretKey, Error> {
+        let bytes = bech32_decode(NSEC_PREFIX, s)?;
+        SecretKey::from_byte_array(&bytes).map_err(|_| Error::InvalidSecretKey)
+    }
+}
+
+/// Computes the id of an event, the SHA-256 hash of its NIP-01 serialization
+/// `[0,<pubkey>,<created_at>,<kind>,<tags>,<content>]`.
+fn event_id(
+    pubkey: &XOnlyPublicKey,
+    created_at: u64,
+    kind: u16,
+    tags: &[Vec<String>],
+    content: &str,
+) -> EventId {
+    let mut buf = [0u8; constants::SCHNORR_PUBLIC_KEY_SIZE * 2];
+    let mut out = String::new();
+    out.push_str("[0,\"");
+    out.push_str(to_hex(&pubkey.serialize(), &mut buf).expect("buffer is large enough"));
+    out.push_str("\",");
+    out.push_str(&created_at.to_string());
+    out.push(',');
+    out.push_str(&kind.to_string());
+    out.push(',');
+    write_tags(&mut out, tags, write_canonical_string);
+    out.push(',');
+    write_canonical_string(&mut out, content);
+    out.push(']');
+    EventId(sha256::Hash::hash(out.as_bytes()).to_byte_array())
+}
+
+/// Appends `tags` to `out` as a JSON array of arrays of strings.
+fn write_tags(out: &mut String, tags: &[Vec<String>], write_string: fn(&mut String, &str)) {
+    out.push('[');
+    for (i, tag) in tags.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('[');
+        for (j, value) in tag.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            write_string(out, value);
+        }
+        out.push(']');
+    }
+    out.push(']');
+}
+
+/// Appends `s` to `out` as a string literal escaped as NIP-01 requires for event ids.
+///
+/// Unlike [`json::write_string`], control characters other than the seven listed by NIP-01 are
+/// written verbatim.
+fn write_canonical_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Parses `2 * N` lowercase hex digits.
+fn parse_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    if s.len() != 2 * N || !s.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    from_hex(s, &mut bytes).ok()?;
+    Some(bytes)
+}
+
+/// Computes the bech32 checksum polynomial of `values`.
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk = 1u32;
+    for value in values {
+        let top = chk >> 25;
+        chk = (chk & 0x1ffffff) << 5 ^ u32::from(value);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+/// Returns the expansion of a human-readable prefix used in the checksum.
+fn expand_prefix(prefix: &str) -> impl Iterator<Item = u8> + '_ {
+    prefix.bytes().map(|c| c >> 5).chain(Some(0)).chain(prefix.bytes().map(|c| c & 31))
+}
+
+/// Encodes a 32-byte payload in bech32 with the lowercase prefix `prefix`.
+fn bech32_encode(prefix: &str, data: &[u8; 32]) -> String {
+    let mut values = [0u8; DATA_LEN + CHECKSUM_LEN];
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut len = 0;
+    for &byte in data {
+        acc = (acc << 8 | u32::from(byte)) & 0x1fff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            values[len] = (acc >> bits & 31) as u8;
+            len += 1;
+        }
+    }
+    values[len] = (acc << (5 - bits) & 31) as u8;
+
+    let chk = polymod(expand_prefix(prefix).chain(values.iter().copied())) ^ 1;
+    for (i, value) in values[DATA_LEN..].iter_mut().enumerate() {
+        *value = (chk >> (5 * (CHECKSUM_LEN - 1 - i)) & 31) as u8;
+    }
+
+    let mut out = String::with_capacity(prefix.len() + 1 + values.len());
+    out.push_str(prefix);
+    out.push('1');
+    out.extend(values.iter().map(|&v| char::from(CHARSET[usize::from(v)])));
+    out
+}
+
+/// Decodes a bech32 string with the lowercase prefix `prefix` and a 32-byte payload.
+fn bech32_decode(prefix: &str, s: &str) -> Result<[u8; 32], Error> {
+    let has_lower = s.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.bytes().any(|c| c.is_ascii_uppercase());
+    if s.len() > MAX_LEN || (has_lower && has_upper) {
+        return Err(Error::InvalidBech32);
+    }
+    let (found, data) = s.rsplit_once('1').ok_or(Error::InvalidBech32)?;
+    if found.is_empty()
+        || data.len() < CHECKSUM_LEN
+        || !found.bytes().all(|c| (33..=126).contains(&c))
+    {
+        return Err(Error::InvalidBech32);
+    }
+
+    let mut values = [0u8; MAX_LEN];
+    for (value, c) in values.iter_mut().zip(data.bytes()) {
+        let c = c.to_ascii_lowercase();
+        *value = CHARSET.iter().position(|&d| d == c).ok_or(Error::InvalidBech32)? as u8;
+    }
+    let values = &values[..data.len()];
+    let found = found.to_ascii_lowercase();
+    if polymod(expand_prefix(&found).chain(values.iter().copied())) != 1 {
+        return Err(Error::InvalidBech32);
+    }
+    if found != prefix {
+        return Err(Error::UnexpectedPrefix);
+    }
+    if values.len() != DATA_LEN + CHECKSUM_LEN {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut out = [0u8; 32];
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut len = 0;
+    for &value in &values[..DATA_LEN] {
+        acc = (acc << 5 | u32::from(value)) & 0x1fff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out[len] = (acc >> bits) as u8;
+            len += 1;
+        }
+    }
+    // The padding bits must be zero.
+    if acc & ((1 << bits) - 1) != 0 {
+        return Err(Error::InvalidBech32);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+
+    #[test]
+    fn nip19_vectors() {
+        let npub = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+        let pk = XOnlyPublicKey::from_npub(npub).unwrap();
+        assert_eq!(
+            pk.serialize(),
+            hex!("3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d")
+        );
+        assert_eq!(pk.to_npub(), npub);
+        assert_eq!(XOnlyPublicKey::from_npub(&npub.to_uppercase()), Ok(pk));
+
+        let nsec = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+        let sk = SecretKey::from_nsec(nsec).unwrap();
+        assert_eq!(
+            sk.secret_bytes(),
+            hex!("67dea2ed018072d675f5415ecfaed7d2597555e202d85b3d65ea4e58d2d92ffa")
+        );
+        assert_eq!(sk.to_nsec(), nsec);
+
+        let note = "note1v93zd6ra8yjzzcqw4tplwggd5rwngxcmw6yu3lfwh5wj84mnnkcsdy9e80";
+        let id = EventId::from_note(note).unwrap();
+        assert_eq!(
+            id.to_byte_array(),
+            hex!("616226e87d392421600eaac3f7210da0dd341b1b7689c8fd2ebd1d23d7739db1")
+        );
+        assert_eq!(id.to_note(), note);
+        assert_eq!(
+            id.to_string(),
+            "616226e87d392421600eaac3f7210da0dd341b1b7689c8fd2ebd1d23d7739db1"
+        );
+        assert_eq!(id.to_string().parse(), Ok(id));
+    }
+
+    #[test]
+    fn bech32_invalid() {
+        let npub = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+        let nsec = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+        let mixed_case = "Npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+        let bad_checksum = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w7";
+        let bad_char = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6wb";
+        // Valid bech32 strings with 33-byte and 31-byte payloads.
+        let too_long = "npub1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5z5tpwxqergd3c8g7ruszzhd5de4";
+        let too_short = "npub1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5z5tpwxqergd3c8g7rupzhmml";
+
+        assert_eq!(XOnlyPublicKey::from_npub(npub).map(|_| ()), Ok(()));
+        assert_eq!(XOnlyPublicKey::from_npub(nsec), Err(Error::UnexpectedPrefix));
+        assert_eq!(SecretKey::from_nsec(npub), Err(Error::UnexpectedPrefix));
+        assert_eq!(EventId::from_note(npub), Err(Error::UnexpectedPrefix));
+        assert_eq!(XOnlyPublicKey::from_npub(mixed_case), Err(Error::InvalidBech32));
+        assert_eq!(XOnlyPublicKey::from_npub(bad_checksum), Err(Error::InvalidBech32));
+        assert_eq!(XOnlyPublicKey::from_npub(bad_char), Err(Error::InvalidBech32));
+        assert_eq!(XOnlyPublicKey::from_npub(too_long), Err(Error::InvalidLength));
+        assert_eq!(XOnlyPublicKey::from_npub(too_short), Err(Error::InvalidLength));
+        assert_eq!(XOnlyPublicKey::from_npub("npub1"), Err(Error::InvalidBech32));
+        assert_eq!(XOnlyPublicKey::from_npub(""), Err(Error::InvalidBech32));
+
+        // The zero key is not a valid secret key.
+        let zero = bech32_encode(NSEC_PREFIX, &[0; 32]);
+        assert_eq!(SecretKey::from_nsec(&zero), Err(Error::InvalidSecretKey));
+        // The field size is not a valid x coordinate.
+        let p = hex!("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f");
+        let npub = bech32_encode(NPUB_PREFIX, &p);
+        assert_eq!(XOnlyPublicKey::from_npub(&npub), Err(Error::InvalidPublicKey));
+    }
+
+    fn unsigned_event() -> UnsignedEvent {
+        UnsignedEvent::new(1700000000, 1, "Hello, nostr!\n\ttab \\ \u{e9} \u{1f600}")
+            .with_tag(&[
+                "e",
+                "5c83da77af1dec6d7289834998ad7aafbd9e2191396d75ec3cc27f5a77226f36",
+                "wss://relay.example",
+            ])
+            .with_tag(&["p", "3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d"])
+            .with_tag(&["t", "caf\u{e9} \"quoted\"\n"])
+    }
+
+    #[test]
+    fn event_id() {
+        let pk = XOnlyPublicKey::from_byte_array(&hex!(
+            "1f16e3e0d7cc4e7b897d23833dcf6f2ace9dd77e2391536dc6c77f82a52886e8"
+        ))
+        .unwrap();
+        assert_eq!(
+            unsigned_event().id(&pk).to_byte_array(),
+            hex!("616226e87d392421600eaac3f7210da0dd341b1b7689c8fd2ebd1d23d7739db1")
+        );
+        assert_eq!(
+            UnsignedEvent::new(0, 0, "").id(&pk).to_byte_array(),
+            hex!("6ae507ce61f473e56fbf4e522c4a3e96d31e627bf4855ba33674d0c9114aba7c")
+        );
+
+        let mut out = String::new();
+        write_canonical_string(&mut out, "\u{8}\u{c}\u{1}\u{7f}");
+        assert_eq!(out, "\"\\b\\f\u{1}\u{7f}\"");
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn sign_and_verify() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_byte_array(&hex!(
+            "5e2c0f6d22b431488867c401ae187aa251291689f5ea282c0524944852e44c4c"
+        ))
+        .unwrap();
+        let keypair = Keypair::from_secret_key(&secp, &sk);
+
+        let event = unsigned_event().sign_with_aux_rand(&secp, &keypair, &[0x42; 32]);
+        assert_eq!(event.id(), unsigned_event().id(&keypair.x_only_public_key().0));
+        assert_eq!(
+            event.signature().to_byte_array(),
+            hex!("386f61876a525a260d86ba718baa6ff3fa2ba376c94f336d7f69f28c2d0bc6118fdb792e09d0fd40db6b4a165f52d0ba7435af3bd1dfb822fb1a2096ae018a2c")
+        );
+        assert_eq!(event.verify(&secp), Ok(()));
+        assert_eq!(Event::from_json(&event.to_json()), Ok(event.clone()));
+
+        let mut tampered = event.clone();
+        tampered.content.push('!');
+        assert_eq!(tampered.verify(&secp), Err(Error::IdMismatch));
+
+        let mut tampered = event.clone();
+        tampered.tags.pop();
+        assert_eq!(tampered.verify(&secp), Err(Error::IdMismatch));
+
+        let mut tampered = event.clone();
+        let mut sig = tampered.sig.to_byte_array();
+        sig[63] ^= 1;
+        tampered.sig = schnorr::Signature::from_byte_array(sig);
+        assert_eq!(tampered.verify(&secp), Err(Error::IncorrectSignature));
+
+        let other = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let mut tampered = event;
+        tampered.pubkey = Keypair::from_secret_key(&secp, &other).x_only_public_key().0;
+        assert_eq!(tampered.verify(&secp), Err(Error::IdMismatch));
+    }
+
+    #[test]
+    #[cfg(all(feature = "rand", feature = "std"))]
+    fn sign_random() {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let event = UnsignedEvent::new(1, 1, "random").sign(&secp, &keypair);
+        assert_eq!(event.verify(&secp), Ok(()));
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn from_json() {
+        let secp = Secp256k1::new();
+        let json = r#"{
+            "kind": 1,
+            "id": "616226e87d392421600eaac3f7210da0dd341b1b7689c8fd2ebd1d23d7739db1",
+            "pubkey": "1f16e3e0d7cc4e7b897d23833dcf6f2ace9dd77e2391536dc6c77f82a52886e8",
+            "created_at": 1700000000,
+            "tags": [
+                ["e", "5c83da77af1dec6d7289834998ad7aafbd9e2191396d75ec3cc27f5a77226f36", "wss://relay.example"],
+                ["p", "3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d"],
+                ["t", "café \"quoted\"\n"]
+            ],
+            "content": "Hello, nostr!\n\ttab \\ é 😀",
+            "sig": "386f61876a525a260d86ba718baa6ff3fa2ba376c94f336d7f69f28c2d0bc6118fdb792e09d0fd40db6b4a165f52d0ba7435af3bd1dfb822fb1a2096ae018a2c",
+            "extra": {"ignored": [null]}
+        }"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.created_at(), 1700000000);
+        assert_eq!(event.kind(), 1);
+        assert_eq!(event.tags(), unsigned_event().tags());
+        assert_eq!(event.content(), unsigned_event().content());
+        assert_eq!(event.verify(&secp), Ok(()));
+
+        // Replaces member `name` of the event, or removes it if `value` is empty.
+        let with = |name: &str, value: &str| {
+            let mut json = event.to_json().replace(&format!("\"{}\":", name), "\"ignored\":");
+            json.pop();
+            if !value.is_empty() {
+                json.push_str(&format!(",\"{}\":{}", name, value));
+            }
+            json.push('}');
+            Event::from_json(&json)
+        };
+        assert_eq!(with("kind", "1"), Ok(event.clone()));
+        assert_eq!(with("kind", ""), Err(Error::InvalidMember("kind")));
+        assert_eq!(with("kind", "65536"), Err(Error::InvalidMember("kind")));
+        assert_eq!(with("kind", "1.0"), Err(Error::InvalidMember("kind")));
+        assert_eq!(with("kind", "\"1\""), Err(Error::InvalidMember("kind")));
+        assert_eq!(with("created_at", "-1"), Err(Error::InvalidMember("created_at")));
+        assert_eq!(with("id", "\"616226\""), Err(Error::InvalidMember("id")));
+        assert_eq!(
+            with("id", "\"616226E87D392421600EAAC3F7210DA0DD341B1B7689C8FD2EBD1D23D7739DB1\""),
+            Err(Error::InvalidMember("id"))
+        );
+        assert_eq!(
+            with("pubkey", "\"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f\""),
+            Err(Error::InvalidMember("pubkey"))
+        );
+        assert_eq!(with("tags", "[\"e\"]"), Err(Error::InvalidMember("tags")));
+        assert_eq!(with("tags", "[[1]]"), Err(Error::InvalidMember("tags")));
+        assert_eq!(with("content", "null"), Err(Error::InvalidMember("content")));
+        assert_eq!(with("sig", "\"00\""), Err(Error::InvalidMember("sig")));
+        assert_eq!(Event::from_json("[]"), Err(Error::InvalidJson));
+    }
+}
@@ -0,0 +1,488 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides the version 2 encrypted payloads of [NIP-44].
+//!
+//! Two parties derive a shared [`ConversationKey`] from the x coordinate of their x-only ECDH
+//! point. Each message is padded, encrypted with ChaCha20 and authenticated with HMAC-SHA256
+//! using keys derived from the conversation key and a random 32-byte nonce, then base64 encoded.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "nostr", feature = "rand", feature = "std"))] {
+//! use secp256k1::nostr::nip44::{self, ConversationKey};
+//! use secp256k1::{rand, Keypair, Secp256k1};
+//!
+//! let secp = Secp256k1::new();
+//! let alice = Keypair::new(&secp, &mut rand::thread_rng());
+//! let bob = Keypair::new(&secp, &mut rand::thread_rng());
+//!
+//! let key = ConversationKey::new(&alice.secret_key(), &bob.x_only_public_key().0);
+//! let payload = nip44::encrypt(&key, "hello bob").unwrap();
+//!
+//! let key = ConversationKey::new(&bob.secret_key(), &alice.x_only_public_key().0);
+//! assert_eq!(nip44::decrypt(&key, &payload).unwrap(), "hello bob");
+//! # }
+//! ```
+//!
+//! [NIP-44]: https://github.com/nostr-protocol/nips/blob/master/44.md
+//!
+
+use core::fmt;
+
+use hashes::hmac::{Hmac, HmacEngine};
+use hashes::{sha256, Hash, HashEngine};
+
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+use crate::base64::{self, Alphabet};
+use crate::{ecdh, Parity, SecretKey, XOnlyPublicKey};
+
+/// The version byte of the payloads produced by this module.
+pub const VERSION: u8 = 2;
+
+/// The minimum length of a plaintext in bytes.
+pub const MIN_PLAINTEXT_LEN: usize = 1;
+
+/// The maximum length of a plaintext in bytes.
+pub const MAX_PLAINTEXT_LEN: usize = 65535;
+
+/// The HKDF salt used to derive conversation keys.
+const SALT: &[u8] = b"nip44-v2";
+
+/// The length of the decoded payload, excluding the padded plaintext: version, nonce and MAC.
+const OVERHEAD: usize = 1 + 32 + 32;
+
+/// The lengths of a base64 payload, for the shortest and longest padded plaintexts.
+const PAYLOAD_LEN: (usize, usize) = (132, 87472);
+
+/// Error encrypting or decrypting a NIP-44 payload.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The plaintext is empty or longer than 65535 bytes.
+    InvalidPlaintextLength(usize),
+    /// The payload uses an encryption version other than 2.
+    UnsupportedVersion,
+    /// The payload is not base64 or has an invalid length.
+    InvalidPayload,
+    /// The MAC does not authenticate the ciphertext.
+    InvalidMac,
+    /// The decrypted plaintext is not correctly padded.
+    InvalidPadding,
+    /// The decrypted plaintext is not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            InvalidPlaintextLength(len) =>
+                write!(f, "invalid plaintext length {} (expected 1 to 65535 bytes)", len),
+            UnsupportedVersion => f.write_str("unsupported NIP-44 encryption version"),
+            InvalidPayload => f.write_str("invalid NIP-44 payload"),
+            InvalidMac => f.write_str("invalid NIP-44 MAC"),
+            InvalidPadding => f.write_str("invalid NIP-44 padding"),
+            InvalidUtf8 => f.write_str("plaintext is not valid UTF-8"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// The key shared by two parties, derived from their x-only ECDH point.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ConversationKey([u8; 32]);
+impl_display_secret!(ConversationKey);
+impl_non_secure_erase!(ConversationKey, 0, [0u8; 32]);
+
+impl ConversationKey {
+    /// Derives the conversation key between `secret_key` and the owner of `public_key`.
+    ///
+    /// The key is symmetric: both parties obtain the same key using their own secret key and the
+    /// other's public key.
+    pub fn new(secret_key: &SecretKey, public_key: &XOnlyPublicKey) -> Self {
+        let mut point = ecdh::shared_secret_point(&public_key.public_key(Parity::Even), secret_key);
+        let key = hkdf_extract(&point[..32]);
+        point.fill(0);
+        ConversationKey(key)
+    }
+
+    /// Constructs a conversation key from its bytes.
+    #[inline]
+    pub fn from_secret_bytes(bytes: [u8; 32]) -> Self { ConversationKey(bytes) }
+
+    /// Returns the conversation key as a byte array.
+    #[inline]
+    pub fn secret_bytes(&self) -> [u8; 32] { self.0 }
+}
+
+/// The per-message keys derived from the conversation key and nonce.
+struct MessageKeys {
+    chacha_key: [u8; 32],
+    chacha_nonce: [u8; 12],
+    hmac_key: [u8; 32],
+}
+
+impl MessageKeys {
+    fn new(key: &ConversationKey, nonce: &[u8; 32]) -> Self {
+        let mut okm = [0u8; 76];
+        hkdf_expand(&key.0, nonce, &mut okm);
+        let mut keys =
+            MessageKeys { chacha_key: [0; 32], chacha_nonce: [0; 12], hmac_key: [0; 32] };
+        keys.chacha_key.copy_from_slice(&okm[..32]);
+        keys.chacha_nonce.copy_from_slice(&okm[32..44]);
+        keys.hmac_key.copy_from_slice(&okm[44..]);
+        okm.fill(0);
+        keys
+    }
+
+    fn mac(&self, nonce: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+        let mut engine = HmacEngine::<sha256::Hash>::new(&self.hmac_key);
+        engine.input(nonce);
+        engine.input(ciphertext);
+        Hmac::from_engine(engine).to_byte_array()
+    }
+}
+
+impl Drop for MessageKeys {
+    fn drop(&mut self) {
+        self.chacha_key.fill(0);
+        self.chacha_nonce.fill(0);
+        self.hmac_key.fill(0);
+    }
+}
+
+/// Returns the length a plaintext of `len` bytes is padded to.
+///
+/// Short plaintexts are padded to 32 bytes; longer ones to a multiple of a chunk size that grows
+/// with the length, leaking only its rough magnitude.
+pub fn padded_len(len: usize) -> usize {
+    if len <= 32 {
+        return 32;
+    }
+    let next_power = len.next_power_of_two();
+    let chunk = if next_power <= 256 { 32 } else { next_power / 8 };
+    chunk * ((len - 1) / chunk + 1)
+}
+
+/// Encrypts `plaintext`, drawing the nonce from the thread-local random number generator.
+#[cfg(all(feature = "rand", feature = "std"))]
+pub fn encrypt(key: &ConversationKey, plaintext: &str) -> Result<String, Error> {
+    encrypt_with_nonce(key, plaintext, &crate::random_32_bytes(&mut rand::thread_rng()))
+}
+
+/// Encrypts `plaintext` with the given nonce.
+///
+/// **The nonce must never be reused with the same conversation key**; it should be generated
+/// uniformly at random for each message. Prefer [`encrypt`] where available.
+pub fn encrypt_with_nonce(
+    key: &ConversationKey,
+    plaintext: &str,
+    nonce: &[u8; 32],
+) -> Result<String, Error> {
+    let len = plaintext.len();
+    if !(MIN_PLAINTEXT_LEN..=MAX_PLAINTEXT_LEN).contains(&len) {
+        return Err(Error::InvalidPlaintextLength(len));
+    }
+    let keys = MessageKeys::new(key, nonce);
+
+    let mut data = Vec::with_capacity(OVERHEAD + 2 + padded_len(len));
+    data.push(VERSION);
+    data.extend_from_slice(nonce);
+    data.extend_from_slice(&(len as u16).to_be_bytes());
+    data.extend_from_slice(plaintext.as_bytes());
+    data.resize(1 + 32 + 2 + padded_len(len), 0);
+    chacha20(&keys.chacha_key, &keys.chacha_nonce, &mut data[33..]);
+    let mac = keys.mac(nonce, &data[33..]);
+    data.extend_from_slice(&mac);
+    Ok(base64::encode(&data, Alphabet::Standard, true))
+}
+
+/// Decrypts and authenticates a payload.
+pub fn decrypt(key: &ConversationKey, payload: &str) -> Result<String, Error> {
+    // A leading '#' marks a payload that is not base64, reserved for future versions.
+    if payload.is_empty() || payload.starts_with('#') {
+        return Err(Error::UnsupportedVersion);
+    }
+    if !(PAYLOAD_LEN.0..=PAYLOAD_LEN.1).contains(&payload.len()) {
+        return Err(Error::InvalidPayload);
+    }
+    let mut data = base64::decode(payload.as_bytes(), Alphabet::Standard, true)
+        .map_err(|_| Error::InvalidPayload)?;
+    if data[0] != VERSION {
+        return Err(Error::UnsupportedVersion);
+    }
+    if data.len() < OVERHEAD + 2 + padded_len(MIN_PLAINTEXT_LEN) {
+        return Err(Error::InvalidPayload);
+    }
+
+    let mac_start = data.len() - 32;
+    let (head, mac) = data.split_at_mut(mac_start);
+    let (nonce, ciphertext) = head[1..].split_at_mut(32);
+    let nonce: &[u8; 32] = (&*nonce).try_into().expect("split at 32");
+    let keys = MessageKeys::new(key, nonce);
+    if !hashes::cmp::fixed_time_eq(&keys.mac(nonce, ciphertext), mac) {
+        return Err(Error::InvalidMac);
+    }
+    chacha20(&keys.chacha_key, &keys.chacha_nonce, ciphertext);
+
+    let len = usize::from(u16::from_be_bytes([ciphertext[0], ciphertext[1]]));
+    if len < MIN_PLAINTEXT_LEN || ciphertext.len() != 2 + padded_len(len) {
+        return Err(Error::InvalidPadding);
+    }
+    String::from_utf8(ciphertext[2..2 + len].to_vec()).map_err(|_| Error::InvalidUtf8)
+}
+
+/// Computes HKDF-Extract with SHA-256 and the NIP-44 salt.
+fn hkdf_extract(ikm: &[u8]) -> [u8; 32] {
+    let mut engine = HmacEngine::<sha256::Hash>::new(SALT);
+    engine.input(ikm);
+    Hmac::from_engine(engine).to_byte_array()
+}
+
+/// Computes HKDF-Expand with SHA-256, filling `out`.
+fn hkdf_expand(prk: &[u8; 32], info: &[u8], out: &mut [u8]) {
+    let mut block = [0u8; 32];
+    for (i, chunk) in out.chunks_mut(32).enumerate() {
+        let mut engine = HmacEngine::<sha256::Hash>::new(prk);
+        if i > 0 {
+            engine.input(&block);
+        }
+        engine.input(info);
+        engine.input(&[i as u8 + 1]);
+        block = Hmac::from_engine(engine).to_byte_array();
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    block.fill(0);
+}
+
+/// Applies the ChaCha20 keystream of RFC 8439, starting at block counter 0, to `data`.
+fn chacha20(key: &[u8; 32], nonce: &[u8; 12], data: &mut [u8]) {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]);
+    for (word, bytes) in state[4..12].iter_mut().zip(key.chunks(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+    }
+    for (word, bytes) in state[13..].iter_mut().zip(nonce.chunks(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+    }
+
+    for (counter, chunk) in data.chunks_mut(64).enumerate() {
+        state[12] = counter as u32;
+        let mut block = state;
+        for _ in 0..10 {
+            quarter_round(&mut block, 0, 4, 8, 12);
+            quarter_round(&mut block, 1, 5, 9, 13);
+            quarter_round(&mut block, 2, 6, 10, 14);
+            quarter_round(&mut block, 3, 7, 11, 15);
+            quarter_round(&mut block, 0, 5, 10, 15);
+            quarter_round(&mut block, 1, 6, 11, 12);
+            quarter_round(&mut block, 2, 7, 8, 13);
+            quarter_round(&mut block, 3, 4, 9, 14);
+        }
+        for (i, byte) in chunk.iter_mut().enumerate() {
+            let word = block[i / 4].wrapping_add(state[i / 4]);
+            *byte ^= word.to_le_bytes()[i % 4];
+        }
+        block.fill(0);
+    }
+    state.fill(0);
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+    #[cfg(not(secp256k1_fuzz))]
+    use crate::{Keypair, Secp256k1};
+
+    /// A valid vector from the NIP-44 specification.
+    struct Vector {
+        conversation_key: [u8; 32],
+        nonce: [u8; 32],
+        plaintext: &'static str,
+        payload: &'static str,
+    }
+
+    fn vectors() -> [Vector; 3] {
+        [
+            Vector {
+                conversation_key: hex!("c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"),
+                nonce: hex!("0000000000000000000000000000000000000000000000000000000000000001"),
+                plaintext: "a",
+                payload: "AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb",
+            },
+            Vector {
+                conversation_key: hex!("c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"),
+                nonce: hex!("f00000000000000000000000000000f00000000000000000000000000000000f"),
+                plaintext: "\u{1f355}\u{1fac3}",
+                payload: "AvAAAAAAAAAAAAAAAAAAAPAAAAAAAAAAAAAAAAAAAAAPSKSK6is9ngkX2+cSq85Th16oRTISAOfhStnixqZziKMDvB0QQzgFZdjLTPicCJaV8nDITO+QfaQ61+KbWQIOO2Yj",
+            },
+            Vector {
+                conversation_key: hex!("3e2b52a63be47d34fe0a80e34e73d436d6963bc8f39827f327057a9986c20a45"),
+                nonce: hex!("b635236c42db20f021bb8d1cdff5ca75dd1a0cc72ea742ad750f33010b24f73b"),
+                plaintext: "表ポあA鷗ŒéＢ逍Üßªąñ丂㐀𠀀",
+                payload: "ArY1I2xC2yDwIbuNHN/1ynXdGgzHLqdCrXUPMwELJPc7s7JqlCMJBAIIjfkpHReBPXeoMCyuClwgbT419jUWU1PwaNl4FEQYKCDKVJz+97Mp3K+Q2YGa77B6gpxB/lr1QgoqpDf7wDVrDmOqGoiPjWDqy8KzLueKDcm9BVP8xeTJIxs=",
+            },
+        ]
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn conversation_key() {
+        // The secret keys of the two parties of each vector.
+        let secret_keys: [([u8; 32], [u8; 32]); 3] = [
+            (
+                hex!("0000000000000000000000000000000000000000000000000000000000000001"),
+                hex!("0000000000000000000000000000000000000000000000000000000000000002"),
+            ),
+            (
+                hex!("0000000000000000000000000000000000000000000000000000000000000002"),
+                hex!("0000000000000000000000000000000000000000000000000000000000000001"),
+            ),
+            (
+                hex!("5c0c523f52a5b6fad39ed2403092df8cebc36318b39383bca6c00808626fab3a"),
+                hex!("4b22aa260e4acb7021e32f38a6cdf4b673c6a277755bfce287e370c924dc936d"),
+            ),
+        ];
+        let secp = Secp256k1::new();
+        for ((sec1, sec2), Vector { conversation_key: key, .. }) in
+            secret_keys.into_iter().zip(vectors())
+        {
+            let sk1 = SecretKey::from_byte_array(&sec1).unwrap();
+            let sk2 = SecretKey::from_byte_array(&sec2).unwrap();
+            let pk1 = Keypair::from_secret_key(&secp, &sk1).x_only_public_key().0;
+            let pk2 = Keypair::from_secret_key(&secp, &sk2).x_only_public_key().0;
+            assert_eq!(ConversationKey::new(&sk1, &pk2).secret_bytes(), key);
+            assert_eq!(ConversationKey::new(&sk2, &pk1).secret_bytes(), key);
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        for Vector { conversation_key: key, nonce, plaintext, payload, .. } in vectors() {
+            let key = ConversationKey::from_secret_bytes(key);
+            assert_eq!(encrypt_with_nonce(&key, plaintext, &nonce).unwrap(), payload);
+            assert_eq!(decrypt(&key, payload).unwrap(), plaintext);
+        }
+
+        let key = ConversationKey::from_secret_bytes([0x11; 32]);
+        let long = "x".repeat(MAX_PLAINTEXT_LEN);
+        let payload = encrypt_with_nonce(&key, &long, &[0x22; 32]).unwrap();
+        assert_eq!(payload.len(), PAYLOAD_LEN.1);
+        assert_eq!(decrypt(&key, &payload).unwrap(), long);
+        assert_eq!(encrypt_with_nonce(&key, "", &[0; 32]), Err(Error::InvalidPlaintextLength(0)));
+        assert_eq!(
+            encrypt_with_nonce(&key, &"x".repeat(65536), &[0; 32]),
+            Err(Error::InvalidPlaintextLength(65536))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "rand", feature = "std"))]
+    fn encrypt_random() {
+        let key = ConversationKey::from_secret_bytes([0x11; 32]);
+        let payload = encrypt(&key, "hello").unwrap();
+        assert_ne!(payload, encrypt(&key, "hello").unwrap());
+        assert_eq!(decrypt(&key, &payload).unwrap(), "hello");
+    }
+
+    #[test]
+    fn padded_lengths() {
+        let lengths = [
+            (1, 32),
+            (16, 32),
+            (32, 32),
+            (33, 64),
+            (37, 64),
+            (45, 64),
+            (49, 64),
+            (64, 64),
+            (65, 96),
+            (100, 128),
+            (111, 128),
+            (200, 224),
+            (250, 256),
+            (320, 320),
+            (383, 384),
+            (384, 384),
+            (400, 448),
+            (500, 512),
+            (512, 512),
+            (515, 640),
+            (700, 768),
+            (800, 896),
+            (900, 1024),
+            (1020, 1024),
+            (65535, 65536),
+        ];
+        for (len, padded) in lengths {
+            assert_eq!(padded_len(len), padded, "{}", len);
+        }
+    }
+
+    #[test]
+    fn decrypt_invalid() {
+        let key = ConversationKey::from_secret_bytes(hex!(
+            "c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"
+        ));
+        let valid = vectors()[0].payload;
+
+        let mut tampered = String::from(valid);
+        tampered.replace_range(100..101, "A");
+        let invalid = [
+            ("", Error::UnsupportedVersion),
+            ("#Agxyz", Error::UnsupportedVersion),
+            (&valid[..131], Error::InvalidPayload),
+            (&tampered, Error::InvalidMac),
+            // Version 1.
+            ("AQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb", Error::UnsupportedVersion),
+            // A zero plaintext length.
+            ("AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABeexn5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCUT/HXgglfhZ8nDHK+fCJSze9Cksz6eUx/xodA7cWFyQ", Error::InvalidPadding),
+            // A one-byte plaintext padded to 64 bytes.
+            ("AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCbprdmPu0c1VY0Evv73AKLQvoaGRmont0jN/cajVSQhaUnaRYltxoOVO4xb0XgfvzWeKRPdVjFeSxFJEc5SkAx8=", Error::InvalidPadding),
+            // A plaintext length exceeding the padded data.
+            ("AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABec0GhDXrsp45q/NvDWoiSJdlY1GWh4QFfkEVduGRzKI7aAKxm31MuWeI4hY49Et/MPgX+B87bzlXfw4rbK9CNfpH", Error::InvalidPadding),
+            ("AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee2Y5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCZfgSr2/RVd8GmMwh2Wx9IAMK7eTgShWJ9das93rYjP2", Error::InvalidUtf8),
+        ];
+        for (payload, error) in invalid {
+            assert_eq!(decrypt(&key, payload), Err(error), "{:?}", payload);
+        }
+
+        let mut not_base64 = String::from(valid);
+        not_base64.replace_range(50..51, "*");
+        assert_eq!(decrypt(&key, &not_base64), Err(Error::InvalidPayload));
+        let too_long = "A".repeat(PAYLOAD_LEN.1 + 4);
+        assert_eq!(decrypt(&key, &too_long), Err(Error::InvalidPayload));
+    }
+
+    #[test]
+    fn chacha20_rfc8439() {
+        // RFC 8439 section 2.4.2, with the block counter starting at 0 instead of 1.
+        let key = hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let nonce = hex!("000000000000004a00000000");
+        let mut data = [0u8; 64 + 114];
+        data[64..].copy_from_slice(b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.");
+        chacha20(&key, &nonce, &mut data);
+        assert_eq!(
+            data[64..],
+            hex!("6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0bf91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d807ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab77937365af90bbf74a35be6b40b8eedf2785e42874d")
+        );
+    }
+}
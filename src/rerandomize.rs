@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides contexts that are re-randomized automatically.
+//!
+//! Randomizing a context blinds the secret-dependent computations performed during signing and
+//! key generation, which is a defense-in-depth measure against side-channel attacks. A context
+//! created by [`Secp256k1::new`] with the `rand` feature enabled is randomized once; long-running
+//! signers may want to refresh the blinding periodically. [`RerandomizingContext`] does this
+//! according to a [`RerandomizePolicy`], taking a lock so that the context is never randomized
+//! while another thread is using it.
+//!
+//! The global context `SECP256K1` is not covered: it hands out references without a lock, so it is
+//! randomized at most once, when it is created. Signers that want periodic re-randomization should
+//! create their own [`RerandomizingContext`] and share it between threads.
+//!
+
+use core::fmt;
+use std::boxed::Box;
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::{Context, Secp256k1, Signing};
+
+/// When a [`RerandomizingContext`] re-randomizes its context.
+///
+/// The default policy never re-randomizes automatically; enable one or both triggers with
+/// [`RerandomizePolicy::after_signings`] and [`RerandomizePolicy::every`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RerandomizePolicy {
+    signings: u64,
+    interval: Option<Duration>,
+}
+
+impl RerandomizePolicy {
+    /// Creates a policy that never re-randomizes automatically.
+    #[inline]
+    pub const fn new() -> Self { RerandomizePolicy { signings: 0, interval: None } }
+
+    /// Re-randomizes after every `n` signing operations; `n = 0` disables this trigger.
+    #[inline]
+    pub const fn after_signings(mut self, n: u64) -> Self {
+        self.signings = n;
+        self
+    }
+
+    /// Re-randomizes on the first use after `interval` has elapsed since the last randomization.
+    #[inline]
+    pub const fn every(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Returns the number of signing operations between re-randomizations, if enabled.
+    #[inline]
+    pub fn signings(&self) -> Option<u64> {
+        if self.signings == 0 {
+            None
+        } else {
+            Some(self.signings)
+        }
+    }
+
+    /// Returns the time between re-randomizations, if enabled.
+    #[inline]
+    pub fn interval(&self) -> Option<Duration> { self.interval }
+}
+
+/// A source of 32-byte seeds for [`Secp256k1::seeded_randomize`].
+///
+/// Implemented for closures; the seeds must be cryptographically secure random data.
+pub trait SeedSource: Send {
+    /// Returns a fresh seed.
+    fn next_seed(&mut self) -> [u8; 32];
+}
+
+impl<F: FnMut() -> [u8; 32] + Send> SeedSource for F {
+    fn next_seed(&mut self) -> [u8; 32] { self() }
+}
+
+/// Bookkeeping shared between users of a [`RerandomizingContext`].
+struct State {
+    policy: RerandomizePolicy,
+    source: Option<Box<dyn SeedSource>>,
+    signings: u64,
+    last_randomized: Instant,
+}
+
+impl State {
+    /// Records a use of the context and returns a seed if the policy says to re-randomize.
+    fn record_use(&mut self, signed: bool) -> Option<[u8; 32]> {
+        if signed {
+            self.signings = self.signings.saturating_add(1);
+        }
+        let by_count = self.policy.signings().map_or(false, |n| self.signings >= n);
+        let by_time = self.policy.interval().map_or(false, |d| self.last_randomized.elapsed() >= d);
+        if by_count || by_time {
+            self.next_seed()
+        } else {
+            None
+        }
+    }
+
+    /// Draws a seed from the seed source and resets the counters.
+    fn next_seed(&mut self) -> Option<[u8; 32]> {
+        let seed = self.source.as_mut()?.next_seed();
+        self.signings = 0;
+        self.last_randomized = Instant::now();
+        Some(seed)
+    }
+}
+
+/// A [`Secp256k1`] context that re-randomizes itself according to a [`RerandomizePolicy`].
+///
+/// The context is only reachable through closures passed to [`RerandomizingContext::sign`] and
+/// [`RerandomizingContext::with`]; these hold a read lock, and re-randomization takes the write
+/// lock, so the type can be shared freely between threads (e.g. in a `static`).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "rand", feature = "std"))] {
+/// use std::time::Duration;
+///
+/// use secp256k1::rerandomize::{RerandomizePolicy, RerandomizingContext};
+/// use secp256k1::{Message, Secp256k1, SecretKey};
+///
+/// let policy = RerandomizePolicy::new().after_signings(1000).every(Duration::from_secs(3600));
+/// let ctx = RerandomizingContext::new(Secp256k1::new(), policy);
+///
+/// let sk = SecretKey::from_byte_array(&[0xcd; 32]).expect("within curve order");
+/// let msg = Message::from_digest([0xab; 32]);
+/// let sig = ctx.sign(|secp| secp.sign_ecdsa(msg, &sk));
+/// # }
+/// ```
+pub struct RerandomizingContext<C: Context> {
+    secp: RwLock<Secp256k1<C>>,
+    state: Mutex<State>,
+}
+
+impl<C: Context> RerandomizingContext<C> {
+    /// Wraps `secp`, drawing seeds from [`rand::thread_rng`].
+    #[cfg(feature = "rand")]
+    pub fn new(secp: Secp256k1<C>, policy: RerandomizePolicy) -> Self {
+        use rand::RngCore;
+
+        Self::with_seed_source(secp, policy, || {
+            let mut seed = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut seed);
+            seed
+        })
+    }
+
+    /// Wraps `secp`, drawing seeds from `source`.
+    pub fn with_seed_source<S>(secp: Secp256k1<C>, policy: RerandomizePolicy, source: S) -> Self
+    where
+        S: SeedSource + 'static,
+    {
+        let source: Box<dyn SeedSource> = Box::new(source);
+        Self::from_parts(secp, policy, Some(source))
+    }
+
+    /// Wraps `secp` without a seed source; the policy has no effect until one is set.
+    fn from_parts(
+        secp: Secp256k1<C>,
+        policy: RerandomizePolicy,
+        source: Option<Box<dyn SeedSource>>,
+    ) -> Self {
+        let state = State { policy, source, signings: 0, last_randomized: Instant::now() };
+        RerandomizingContext { secp: RwLock::new(secp), state: Mutex::new(state) }
+    }
+
+    /// Returns the current policy.
+    pub fn policy(&self) -> RerandomizePolicy { self.state().policy }
+
+    /// Replaces the policy; counters accumulated so far are kept.
+    pub fn set_policy(&self, policy: RerandomizePolicy) { self.state().policy = policy; }
+
+    /// Replaces the source of seeds used for re-randomization.
+    pub fn set_seed_source<S: SeedSource + 'static>(&self, source: S) {
+        self.state().source = Some(Box::new(source));
+    }
+
+    /// Returns the number of signing operations since the context was last randomized.
+    pub fn signings_since_randomize(&self) -> u64 { self.state().signings }
+
+    /// Runs `f` with the context without counting it as a signing operation.
+    ///
+    /// The context is re-randomized afterwards if the policy's interval has elapsed.
+    pub fn with<R>(&self, f: impl FnOnce(&Secp256k1<C>) -> R) -> R { self.run(false, f) }
+
+    /// Re-randomizes the context now, regardless of the policy.
+    ///
+    /// Does nothing if there is no seed source.
+    pub fn rerandomize(&self) {
+        let seed = self.state().next_seed();
+        if let Some(seed) = seed {
+            self.randomize_with(&seed);
+        }
+    }
+
+    /// Consumes `self`, returning the wrapped context.
+    pub fn into_inner(self) -> Secp256k1<C> {
+        self.secp.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn run<R>(&self, signed: bool, f: impl FnOnce(&Secp256k1<C>) -> R) -> R {
+        let ret = f(&self.secp.read().unwrap_or_else(PoisonError::into_inner));
+        // The read lock is released at the end of the statement above, before re-randomization
+        // takes the write lock.
+        let seed = self.state().record_use(signed);
+        if let Some(seed) = seed {
+            self.randomize_with(&seed);
+        }
+        ret
+    }
+
+    fn randomize_with(&self, seed: &[u8; 32]) {
+        self.secp.write().unwrap_or_else(PoisonError::into_inner).seeded_randomize(seed);
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<C: Signing> RerandomizingContext<C> {
+    /// Runs `f` with the context, counting it as one signing operation.
+    ///
+    /// Use this for anything that operates on secret keys (signing, key generation, tweaking).
+    /// The context is re-randomized afterwards if the policy says so.
+    pub fn sign<R>(&self, f: impl FnOnce(&Secp256k1<C>) -> R) -> R { self.run(true, f) }
+}
+
+impl<C: Context> fmt::Debug for RerandomizingContext<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("RerandomizingContext")
+            .field("policy", &state.policy)
+            .field("has_seed_source", &state.source.is_some())
+            .field("signings", &state.signings)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{Message, SecretKey};
+
+    fn counting_source() -> (Arc<AtomicUsize>, impl SeedSource) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let source_count = Arc::clone(&count);
+        let source = move || {
+            let n = source_count.fetch_add(1, Ordering::SeqCst);
+            [n as u8 + 1; 32]
+        };
+        (count, source)
+    }
+
+    #[test]
+    fn policy() {
+        let policy = RerandomizePolicy::new();
+        assert_eq!(policy, RerandomizePolicy::default());
+        assert_eq!(policy.signings(), None);
+        assert_eq!(policy.interval(), None);
+
+        let policy = policy.after_signings(10).every(Duration::from_secs(1));
+        assert_eq!(policy.signings(), Some(10));
+        assert_eq!(policy.interval(), Some(Duration::from_secs(1)));
+        assert_eq!(policy.after_signings(0).signings(), None);
+    }
+
+    #[test]
+    fn rerandomize_after_signings() {
+        let (count, source) = counting_source();
+        let policy = RerandomizePolicy::new().after_signings(3);
+        let ctx = RerandomizingContext::with_seed_source(Secp256k1::new(), policy, source);
+
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let msg = Message::from_digest([0xab; 32]);
+        let expected = Secp256k1::new().sign_ecdsa(msg, &sk);
+        for _ in 0..7 {
+            // Randomization does not affect the (deterministic) output.
+            assert_eq!(ctx.sign(|secp| secp.sign_ecdsa(msg, &sk)), expected);
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        assert_eq!(ctx.signings_since_randomize(), 1);
+
+        // Non-signing use does not count.
+        ctx.with(|secp| secp.verify_ecdsa(msg, &expected, &sk.public_key(secp)).unwrap());
+        assert_eq!(ctx.signings_since_randomize(), 1);
+
+        ctx.rerandomize();
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+        assert_eq!(ctx.signings_since_randomize(), 0);
+    }
+
+    #[test]
+    fn rerandomize_on_timer() {
+        let (count, source) = counting_source();
+        let policy = RerandomizePolicy::new().every(Duration::from_millis(1));
+        let ctx = RerandomizingContext::with_seed_source(Secp256k1::new(), policy, source);
+
+        std::thread::sleep(Duration::from_millis(5));
+        ctx.with(|_| ());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn default_policy_never_rerandomizes() {
+        let (count, source) = counting_source();
+        let ctx = RerandomizingContext::with_seed_source(
+            Secp256k1::new(),
+            RerandomizePolicy::new(),
+            source,
+        );
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        for _ in 0..10 {
+            ctx.sign(|secp| sk.public_key(secp));
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        ctx.set_policy(RerandomizePolicy::new().after_signings(5));
+        ctx.sign(|secp| sk.public_key(secp));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let (count, source) = counting_source();
+        let policy = RerandomizePolicy::new().after_signings(10);
+        let ctx =
+            Arc::new(RerandomizingContext::with_seed_source(Secp256k1::new(), policy, source));
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let msg = Message::from_digest([0xab; 32]);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let ctx = Arc::clone(&ctx);
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        let sig = ctx.sign(|secp| secp.sign_ecdsa(msg, &sk));
+                        ctx.with(|secp| {
+                            secp.verify_ecdsa(msg, &sig, &sk.public_key(secp)).unwrap()
+                        });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 10);
+    }
+}
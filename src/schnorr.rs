@@ -3,15 +3,17 @@
 //! Support for schnorr signatures.
 //!
 
-use core::{fmt, ptr, str};
+use core::{fmt, ptr, slice, str};
 
 #[cfg(feature = "rand")]
 use rand::{CryptoRng, Rng};
 use secp256k1_sys::SchnorrSigExtraParams;
 
+use crate::callback::PanicGuard;
+use crate::ffi::types::{c_int, c_uchar, c_void, size_t};
 use crate::ffi::{self, CPtr};
 use crate::key::{Keypair, XOnlyPublicKey};
-#[cfg(feature = "global-context")]
+#[cfg(feature = "global-context-no-std")]
 use crate::SECP256K1;
 use crate::{constants, from_hex, Error, Secp256k1, Signing, Verification};
 
@@ -105,12 +107,206 @@ impl Signature {
 
     /// Verifies a schnorr signature for `msg` using `pk` and the global [`SECP256K1`] context.
     #[inline]
-    #[cfg(feature = "global-context")]
+    #[cfg(feature = "global-context-no-std")]
     pub fn verify(&self, msg: &[u8], pk: &XOnlyPublicKey) -> Result<(), Error> {
         SECP256K1.verify_schnorr(self, msg, pk)
     }
 }
 
+/// The algorithm tag BIP 340 uses to derive nonces.
+pub const BIP340_NONCE_TAG: &[u8] = b"BIP0340/nonce";
+
+/// The BIP 340 nonce generation function used by [`Secp256k1::sign_schnorr_with_aux_rand`].
+///
+/// This is the building block for custom nonce functions that wrap, or fall back to, the default
+/// behavior. See [`SchnorrSignParams::nonce_fn`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bip340 {
+    aux_rand: Option<[u8; 32]>,
+}
+
+impl Bip340 {
+    /// Creates the BIP 340 nonce function without auxiliary random data, as used by
+    /// [`Secp256k1::sign_schnorr_no_aux_rand`].
+    #[inline]
+    pub const fn new() -> Self { Bip340 { aux_rand: None } }
+
+    /// Creates the BIP 340 nonce function using the given auxiliary random data, as used by
+    /// [`Secp256k1::sign_schnorr_with_aux_rand`].
+    #[inline]
+    pub const fn with_aux_rand(aux_rand: [u8; 32]) -> Self { Bip340 { aux_rand: Some(aux_rand) } }
+
+    /// Returns the auxiliary random data mixed into the nonce derivation, if any.
+    #[inline]
+    pub fn aux_rand(&self) -> Option<&[u8; 32]> { self.aux_rand.as_ref() }
+
+    /// Computes the nonce for signing `msg` with secret key `key`, whose x-only public key is
+    /// `xonly_pk`, using `algo` as the tag of the nonce hash.
+    ///
+    /// The arguments mirror those passed to [`SchnorrSignParams::nonce_fn`]; BIP 340 itself uses
+    /// [`BIP340_NONCE_TAG`]. Returns `None` if libsecp256k1 fails to derive a nonce, which does
+    /// not happen for valid inputs.
+    pub fn nonce(
+        &self,
+        msg: &[u8],
+        key: &[u8; 32],
+        xonly_pk: &[u8; 32],
+        algo: &[u8],
+    ) -> Option<[u8; 32]> {
+        let mut nonce = [0u8; 32];
+        let data_ptr = match self.aux_rand {
+            Some(ref aux_rand) => aux_rand.as_c_ptr() as *mut c_void,
+            None => ptr::null_mut(),
+        };
+        unsafe {
+            let nonce_fn = ffi::secp256k1_nonce_function_bip340
+                .expect("libsecp256k1 always provides the BIP 340 nonce function");
+            let ret = nonce_fn(
+                nonce.as_mut_c_ptr(),
+                msg.as_c_ptr(),
+                msg.len(),
+                key.as_c_ptr(),
+                xonly_pk.as_c_ptr(),
+                algo.as_c_ptr(),
+                algo.len(),
+                data_ptr,
+            );
+            if ret == 1 {
+                Some(nonce)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// The default type parameter of [`SchnorrSignParams`], used when no custom nonce function is set.
+pub type DefaultNonceFn =
+    fn(&[u8], &[u8; 32], &[u8; 32], &[u8], Option<&[u8; 32]>) -> Option<[u8; 32]>;
+
+/// Parameters for creating schnorr signatures with [`Secp256k1::sign_schnorr_with_params`].
+///
+/// By default signatures are created exactly like [`Secp256k1::sign_schnorr_no_aux_rand`]. The
+/// builder methods allow supplying auxiliary random data, overriding the algorithm tag used to
+/// derive the nonce, and replacing the nonce function altogether, as needed by protocols such as
+/// DLC oracles or sign-to-contract.
+///
+/// **Using a badly designed nonce function will leak your secret key.** Reusing a nonce for two
+/// different messages, or using a predictable or biased nonce, lets anyone who sees the signatures
+/// compute the secret key.
+pub struct SchnorrSignParams<'a, F = DefaultNonceFn> {
+    aux_rand: Option<&'a [u8; 32]>,
+    algo: Option<&'a [u8]>,
+    nonce_fn: Option<F>,
+}
+
+impl<'a> SchnorrSignParams<'a> {
+    /// Creates parameters for signing without auxiliary random data using the BIP 340 nonce
+    /// function.
+    #[inline]
+    pub fn new() -> Self { SchnorrSignParams { aux_rand: None, algo: None, nonce_fn: None } }
+}
+
+impl<'a> Default for SchnorrSignParams<'a> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<'a, F> SchnorrSignParams<'a, F>
+where
+    F: FnMut(&[u8], &[u8; 32], &[u8; 32], &[u8], Option<&[u8; 32]>) -> Option<[u8; 32]>,
+{
+    /// Sets the auxiliary random data passed to the nonce function.
+    #[inline]
+    pub fn aux_rand(mut self, aux_rand: &'a [u8; 32]) -> Self {
+        self.aux_rand = Some(aux_rand);
+        self
+    }
+
+    /// Overrides the algorithm tag passed to the nonce function (default: [`BIP340_NONCE_TAG`]).
+    ///
+    /// With the BIP 340 nonce function this changes the tag of the nonce hash, producing
+    /// signatures that are valid but differ from standard BIP 340 ones.
+    #[inline]
+    pub fn algo(mut self, algo: &'a [u8]) -> Self {
+        self.algo = Some(algo);
+        self
+    }
+
+    /// Replaces the BIP 340 nonce function with `nonce_fn`.
+    ///
+    /// `nonce_fn` is called with the message, the secret key (negated if needed so that the
+    /// public key has an even Y coordinate), the serialized x-only public key, the algorithm tag
+    /// and the auxiliary random data. It returns the 32-byte nonce, or `None` to abort signing.
+    /// Use [`Bip340`] to reproduce the default nonce derivation from inside `nonce_fn`.
+    ///
+    /// If `nonce_fn` panics the panic is propagated once control returns from libsecp256k1. This
+    /// requires the `std` feature; without it a panic in `nonce_fn` must not unwind.
+    #[inline]
+    pub fn nonce_fn<G>(self, nonce_fn: G) -> SchnorrSignParams<'a, G>
+    where
+        G: FnMut(&[u8], &[u8; 32], &[u8; 32], &[u8], Option<&[u8; 32]>) -> Option<[u8; 32]>,
+    {
+        SchnorrSignParams { aux_rand: self.aux_rand, algo: self.algo, nonce_fn: Some(nonce_fn) }
+    }
+}
+
+impl<F> fmt::Debug for SchnorrSignParams<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SchnorrSignParams")
+            .field("aux_rand", &self.aux_rand.map(|_| "<aux rand>"))
+            .field("algo", &self.algo)
+            .field("nonce_fn", &self.nonce_fn.as_ref().map(|_| "<custom>"))
+            .finish()
+    }
+}
+
+/// State handed to libsecp256k1 as the `data` pointer of [`nonce_fn_trampoline`].
+struct NonceFnState<'a, F> {
+    params: SchnorrSignParams<'a, F>,
+    guard: PanicGuard,
+}
+
+/// Calls the nonce function configured in `data` on behalf of `secp256k1_schnorrsig_sign_custom`.
+unsafe extern "C" fn nonce_fn_trampoline<F>(
+    nonce32: *mut c_uchar,
+    msg: *const c_uchar,
+    msg_len: size_t,
+    key32: *const c_uchar,
+    xonly_pk32: *const c_uchar,
+    algo: *const c_uchar,
+    algo_len: size_t,
+    data: *mut c_void,
+) -> c_int
+where
+    F: FnMut(&[u8], &[u8; 32], &[u8; 32], &[u8], Option<&[u8; 32]>) -> Option<[u8; 32]>,
+{
+    let state = &mut *(data as *mut NonceFnState<F>);
+    let msg = if msg_len == 0 { &[][..] } else { slice::from_raw_parts(msg, msg_len) };
+    let key = &*(key32 as *const [u8; 32]);
+    let xonly_pk = &*(xonly_pk32 as *const [u8; 32]);
+    let algo = match state.params.algo {
+        Some(algo) => algo,
+        None => slice::from_raw_parts(algo, algo_len),
+    };
+    let aux_rand = state.params.aux_rand;
+
+    let nonce = match state.params.nonce_fn {
+        Some(ref mut nonce_fn) =>
+            state.guard.call(None, || nonce_fn(msg, key, xonly_pk, algo, aux_rand)),
+        None => {
+            let bip340 = Bip340 { aux_rand: aux_rand.copied() };
+            bip340.nonce(msg, key, xonly_pk, algo)
+        }
+    };
+    match nonce {
+        Some(nonce) => {
+            ptr::copy_nonoverlapping(nonce.as_ptr(), nonce32, 32);
+            1
+        }
+        None => 0,
+    }
+}
+
 impl<C: Signing> Secp256k1<C> {
     fn sign_schnorr_helper(
         &self,
@@ -172,6 +368,72 @@ impl<C: Signing> Secp256k1<C> {
         rng.fill_bytes(&mut aux);
         self.sign_schnorr_helper(msg, keypair, aux.as_c_ptr() as *const ffi::types::c_uchar)
     }
+
+    /// Creates a schnorr signature using custom parameters, see [`SchnorrSignParams`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NonceGenerationFailed`] if the nonce function fails or returns a nonce
+    /// that is zero or overflows the curve order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "alloc", not(secp256k1_fuzz)))] {
+    /// use secp256k1::schnorr::SchnorrSignParams;
+    /// use secp256k1::{Keypair, Secp256k1};
+    ///
+    /// let secp = Secp256k1::new();
+    /// let keypair = Keypair::from_seckey_slice(&secp, &[0xcd; 32]).expect("within curve order");
+    /// let aux_rand = [0xab; 32];
+    ///
+    /// let params = SchnorrSignParams::new().aux_rand(&aux_rand);
+    /// let sig = secp.sign_schnorr_with_params(b"msg", &keypair, params).expect("BIP 340 never fails");
+    /// assert_eq!(sig, secp.sign_schnorr_with_aux_rand(b"msg", &keypair, &aux_rand));
+    ///
+    /// let params = SchnorrSignParams::new().aux_rand(&aux_rand).algo(b"my-protocol/nonce");
+    /// let sig = secp.sign_schnorr_with_params(b"msg", &keypair, params).expect("BIP 340 never fails");
+    /// assert!(secp.verify_schnorr(&sig, b"msg", &keypair.x_only_public_key().0).is_ok());
+    /// # }
+    /// ```
+    pub fn sign_schnorr_with_params<F>(
+        &self,
+        msg: &[u8],
+        keypair: &Keypair,
+        params: SchnorrSignParams<F>,
+    ) -> Result<Signature, Error>
+    where
+        F: FnMut(&[u8], &[u8; 32], &[u8; 32], &[u8], Option<&[u8; 32]>) -> Option<[u8; 32]>,
+    {
+        if params.algo.is_none() && params.nonce_fn.is_none() {
+            let aux_rand = params.aux_rand.map_or(ptr::null(), |aux_rand| aux_rand.as_c_ptr());
+            return Ok(self.sign_schnorr_helper(msg, keypair, aux_rand));
+        }
+
+        let mut state = NonceFnState { params, guard: PanicGuard::default() };
+        let ret = unsafe {
+            let mut sig = [0u8; constants::SCHNORR_SIGNATURE_SIZE];
+            let extra = SchnorrSigExtraParams::new(
+                Some(nonce_fn_trampoline::<F>),
+                &mut state as *mut NonceFnState<F> as *const c_void,
+            );
+            let ret = ffi::secp256k1_schnorrsig_sign_custom(
+                self.ctx.as_ptr(),
+                sig.as_mut_c_ptr(),
+                msg.as_c_ptr(),
+                msg.len(),
+                keypair.as_c_ptr(),
+                &extra,
+            );
+            if ret == 1 {
+                Ok(Signature(sig))
+            } else {
+                Err(Error::NonceGenerationFailed)
+            }
+        };
+        state.guard.resume();
+        ret
+    }
 }
 
 impl<C: Verification> Secp256k1<C> {
@@ -287,6 +549,86 @@ mod tests {
         assert_eq!(expected_sig, sig);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[cfg(not(secp256k1_fuzz))] // fuzz-sigs ignore the nonce function
+    fn schnorr_sign_with_params() {
+        let secp = Secp256k1::new();
+
+        let msg = hex_32!("E48441762FB75010B2AA31A512B62B4148AA3FB08EB0765D76B252559064A614");
+        let kp = Keypair::from_seckey_str(
+            &secp,
+            "688C77BC2D5AAFF5491CF309D4753B732135470D05B7B2CD21ADD0744FE97BEF",
+        )
+        .unwrap();
+        let (pk, _) = kp.x_only_public_key();
+        let aux_rand = hex_32!("02CCE08E913F22A36C5648D6405A2C7C50106E7AA2F1649E381C7F09D16B80AB");
+        let expected = secp.sign_schnorr_with_aux_rand(&msg, &kp, &aux_rand);
+
+        let params = SchnorrSignParams::new().aux_rand(&aux_rand);
+        assert_eq!(secp.sign_schnorr_with_params(&msg, &kp, params), Ok(expected));
+
+        let params = SchnorrSignParams::new();
+        let no_aux = secp.sign_schnorr_no_aux_rand(&msg, &kp);
+        assert_eq!(secp.sign_schnorr_with_params(&msg, &kp, params), Ok(no_aux));
+
+        // Explicitly setting the default tag takes the custom code path but changes nothing.
+        let params = SchnorrSignParams::new().aux_rand(&aux_rand).algo(BIP340_NONCE_TAG);
+        assert_eq!(secp.sign_schnorr_with_params(&msg, &kp, params), Ok(expected));
+
+        let params = SchnorrSignParams::new().aux_rand(&aux_rand).algo(b"DLC/oracle/nonce/v0");
+        let sig = secp.sign_schnorr_with_params(&msg, &kp, params).unwrap();
+        assert_ne!(sig, expected);
+        assert!(secp.verify_schnorr(&sig, &msg, &pk).is_ok());
+
+        // A custom nonce function wrapping the BIP 340 one reproduces standard signatures.
+        let params = SchnorrSignParams::new().aux_rand(&aux_rand).nonce_fn(
+            |msg, key, xonly_pk, algo, aux| {
+                assert_eq!(algo, BIP340_NONCE_TAG);
+                assert_eq!(aux, Some(&aux_rand));
+                assert_eq!(xonly_pk, &pk.serialize());
+                Bip340::with_aux_rand(aux_rand).nonce(msg, key, xonly_pk, algo)
+            },
+        );
+        assert_eq!(secp.sign_schnorr_with_params(&msg, &kp, params), Ok(expected));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[cfg(not(secp256k1_fuzz))] // fuzz-sigs ignore the nonce function
+    fn schnorr_sign_with_custom_nonce_fn() {
+        let secp = Secp256k1::new();
+        let kp = Keypair::from_seckey_slice(&secp, &[0xcd; 32]).unwrap();
+        let (pk, _) = kp.x_only_public_key();
+        let msg = b"variable length message";
+
+        let mut calls = 0;
+        // With nonce 1 the signature's R value is the generator, which has an even Y coordinate.
+        let params = SchnorrSignParams::new().algo(b"custom").nonce_fn(|m, _, _, algo, aux| {
+            assert_eq!(m, msg);
+            assert_eq!(algo, b"custom");
+            assert!(aux.is_none());
+            calls += 1;
+            Some(constants::ONE)
+        });
+        let sig = secp.sign_schnorr_with_params(msg, &kp, params).unwrap();
+        assert_eq!(sig[..32], constants::GENERATOR_X);
+        assert!(secp.verify_schnorr(&sig, msg, &pk).is_ok());
+        assert_eq!(calls, 1);
+
+        let params = SchnorrSignParams::new().nonce_fn(|_, _, _, _, _| None);
+        assert_eq!(
+            secp.sign_schnorr_with_params(msg, &kp, params),
+            Err(Error::NonceGenerationFailed)
+        );
+
+        let params = SchnorrSignParams::new().nonce_fn(|_, _, _, _, _| Some([0u8; 32]));
+        assert_eq!(
+            secp.sign_schnorr_with_params(msg, &kp, params),
+            Err(Error::NonceGenerationFailed)
+        );
+    }
+
     #[test]
     #[cfg(not(secp256k1_fuzz))] // fixed sig vectors can't work with fuzz-sigs
     #[cfg(feature = "alloc")]
@@ -0,0 +1,352 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides power-on self tests of the library.
+//!
+//! [`Secp256k1::selftest`] runs libsecp256k1's own platform checks followed by known-answer tests
+//! (KATs) of ECDSA, Schnorr, ECDH and ElligatorSwift against published test vectors. Running it
+//! once at process start detects miscompilation or a broken platform before any key material is
+//! handled.
+//!
+
+use core::fmt;
+
+use crate::ellswift::{ElligatorSwift, ElligatorSwiftSharedSecret, Party};
+use crate::{
+    ecdh, ecdsa, ffi, schnorr, Keypair, Message, PublicKey, Secp256k1, SecretKey, Signing,
+    Verification, XOnlyPublicKey,
+};
+
+/// A known-answer test run by [`Secp256k1::selftest`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum SelfTest {
+    /// ECDSA signing with RFC6979 nonces and verification.
+    Ecdsa,
+    /// BIP-340 Schnorr signing and verification.
+    Schnorr,
+    /// ECDH using the default SHA256-based hash function.
+    Ecdh,
+    /// ElligatorSwift decoding and BIP-324 x-only ECDH.
+    ElligatorSwift,
+}
+
+impl SelfTest {
+    /// All known-answer tests, in the order they are run.
+    pub const ALL: [SelfTest; 4] =
+        [SelfTest::Ecdsa, SelfTest::Schnorr, SelfTest::Ecdh, SelfTest::ElligatorSwift];
+
+    fn run<C: Signing + Verification>(self, secp: &Secp256k1<C>) -> bool {
+        match self {
+            SelfTest::Ecdsa => kat_ecdsa(secp),
+            SelfTest::Schnorr => kat_schnorr(secp),
+            SelfTest::Ecdh => kat_ecdh(),
+            SelfTest::ElligatorSwift => kat_ellswift(),
+        }
+    }
+}
+
+impl fmt::Display for SelfTest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            SelfTest::Ecdsa => "ECDSA",
+            SelfTest::Schnorr => "Schnorr",
+            SelfTest::Ecdh => "ECDH",
+            SelfTest::ElligatorSwift => "ElligatorSwift",
+        };
+        f.write_str(s)
+    }
+}
+
+/// The results of [`Secp256k1::selftest`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SelfTestReport {
+    /// Bit `i` is set if `SelfTest::ALL[i]` failed.
+    failed: u8,
+}
+
+impl SelfTestReport {
+    /// Returns `true` if every known-answer test passed.
+    #[inline]
+    pub fn passed(&self) -> bool { self.failed == 0 }
+
+    /// Returns `true` if the given known-answer test passed.
+    #[inline]
+    pub fn test_passed(&self, test: SelfTest) -> bool { self.failed & (1 << test as u8) == 0 }
+
+    /// Returns an iterator over the known-answer tests that failed.
+    pub fn failures(&self) -> impl Iterator<Item = SelfTest> + '_ {
+        SelfTest::ALL.into_iter().filter(move |test| !self.test_passed(*test))
+    }
+
+    /// Returns whether the library was built with the `lowmemory` precomputation tables.
+    ///
+    /// Signing uses different code paths for the two table sizes, so this records which one the
+    /// report covers.
+    #[inline]
+    pub fn lowmemory(&self) -> bool { cfg!(feature = "lowmemory") }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tables = if self.lowmemory() { "low-memory" } else { "default" };
+        write!(f, "self test ({} tables): ", tables)?;
+        if self.passed() {
+            return f.write_str("passed");
+        }
+        f.write_str("failed")?;
+        for (i, test) in self.failures().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(f, "{}{}", sep, test)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Signing + Verification> Secp256k1<C> {
+    /// Runs the library's self tests using this context.
+    ///
+    /// This first calls libsecp256k1's built-in `secp256k1_selftest`, which checks the platform
+    /// the library was built for, and then runs the known-answer tests listed in [`SelfTest`].
+    ///
+    /// # Panics
+    ///
+    /// If the built-in platform check fails. libsecp256k1 reports this through its default error
+    /// callback, which panics (see [`Secp256k1::set_error_callback`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "alloc", not(secp256k1_fuzz)))] {
+    /// use secp256k1::Secp256k1;
+    ///
+    /// let report = Secp256k1::new().selftest();
+    /// assert!(report.passed(), "{}", report);
+    /// # }
+    /// ```
+    pub fn selftest(&self) -> SelfTestReport {
+        unsafe { ffi::secp256k1_selftest() };
+
+        let mut failed = 0;
+        for test in SelfTest::ALL {
+            if !test.run(self) {
+                failed |= 1 << test as u8;
+            }
+        }
+        SelfTestReport { failed }
+    }
+}
+
+/// Runs the library's self tests using a freshly created context.
+///
+/// See [`Secp256k1::selftest`] for details; use that method directly to test a preallocated
+/// context.
+#[cfg(feature = "alloc")]
+pub fn selftest() -> SelfTestReport { Secp256k1::new().selftest() }
+
+/// ECDSA: private key 1 signing SHA256("Satoshi Nakamoto") with RFC6979 nonces.
+fn kat_ecdsa<C: Signing + Verification>(secp: &Secp256k1<C>) -> bool {
+    const SECRET_KEY: [u8; 32] = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01,
+    ];
+    const DIGEST: [u8; 32] = [
+        0xa0, 0xdc, 0x65, 0xff, 0xca, 0x79, 0x98, 0x73, 0xcb, 0xea, 0x0a, 0xc2, 0x74, 0x01, 0x5b,
+        0x95, 0x26, 0x50, 0x5d, 0xaa, 0xae, 0xd3, 0x85, 0x15, 0x54, 0x25, 0xf7, 0x33, 0x77, 0x04,
+        0x88, 0x3e,
+    ];
+    const SIGNATURE: [u8; 64] = [
+        0x93, 0x4b, 0x1e, 0xa1, 0x0a, 0x4b, 0x3c, 0x17, 0x57, 0xe2, 0xb0, 0xc0, 0x17, 0xd0, 0xb6,
+        0x14, 0x3c, 0xe3, 0xc9, 0xa7, 0xe6, 0xa4, 0xa4, 0x98, 0x60, 0xd7, 0xa6, 0xab, 0x21, 0x0e,
+        0xe3, 0xd8, 0x24, 0x42, 0xce, 0x9d, 0x2b, 0x91, 0x60, 0x64, 0x10, 0x80, 0x14, 0x78, 0x3e,
+        0x92, 0x3e, 0xc3, 0x6b, 0x49, 0x74, 0x3e, 0x2f, 0xfa, 0x1c, 0x44, 0x96, 0xf0, 0x1a, 0x51,
+        0x2a, 0xaf, 0xd9, 0xe5,
+    ];
+
+    let sk = match SecretKey::from_byte_array(&SECRET_KEY) {
+        Ok(sk) => sk,
+        Err(_) => return false,
+    };
+    let expected = match ecdsa::Signature::from_compact(&SIGNATURE) {
+        Ok(sig) => sig,
+        Err(_) => return false,
+    };
+    let pk = PublicKey::from_secret_key(secp, &sk);
+    let msg = Message::from_digest(DIGEST);
+    let mut other_msg = DIGEST;
+    other_msg[0] ^= 1;
+
+    secp.sign_ecdsa(msg, &sk) == expected
+        && secp.verify_ecdsa(msg, &expected, &pk).is_ok()
+        && secp.verify_ecdsa(Message::from_digest(other_msg), &expected, &pk).is_err()
+}
+
+/// Schnorr: BIP-340 test vector 0.
+fn kat_schnorr<C: Signing + Verification>(secp: &Secp256k1<C>) -> bool {
+    const SECRET_KEY: [u8; 32] = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x03,
+    ];
+    const PUBLIC_KEY: [u8; 32] = [
+        0xf9, 0x30, 0x8a, 0x01, 0x92, 0x58, 0xc3, 0x10, 0x49, 0x34, 0x4f, 0x85, 0xf8, 0x9d, 0x52,
+        0x29, 0xb5, 0x31, 0xc8, 0x45, 0x83, 0x6f, 0x99, 0xb0, 0x86, 0x01, 0xf1, 0x13, 0xbc, 0xe0,
+        0x36, 0xf9,
+    ];
+    const AUX_RAND: [u8; 32] = [0; 32];
+    const MESSAGE: [u8; 32] = [0; 32];
+    const SIGNATURE: [u8; 64] = [
+        0xe9, 0x07, 0x83, 0x1f, 0x80, 0x84, 0x8d, 0x10, 0x69, 0xa5, 0x37, 0x1b, 0x40, 0x24, 0x10,
+        0x36, 0x4b, 0xdf, 0x1c, 0x5f, 0x83, 0x07, 0xb0, 0x08, 0x4c, 0x55, 0xf1, 0xce, 0x2d, 0xca,
+        0x82, 0x15, 0x25, 0xf6, 0x6a, 0x4a, 0x85, 0xea, 0x8b, 0x71, 0xe4, 0x82, 0xa7, 0x4f, 0x38,
+        0x2d, 0x2c, 0xe5, 0xeb, 0xee, 0xe8, 0xfd, 0xb2, 0x17, 0x2f, 0x47, 0x7d, 0xf4, 0x90, 0x0d,
+        0x31, 0x05, 0x36, 0xc0,
+    ];
+
+    let keypair = match Keypair::from_seckey_slice(secp, &SECRET_KEY) {
+        Ok(keypair) => keypair,
+        Err(_) => return false,
+    };
+    let pk = match XOnlyPublicKey::from_byte_array(&PUBLIC_KEY) {
+        Ok(pk) => pk,
+        Err(_) => return false,
+    };
+    let expected = schnorr::Signature::from_byte_array(SIGNATURE);
+    let mut other_msg = MESSAGE;
+    other_msg[0] ^= 1;
+
+    keypair.x_only_public_key().0 == pk
+        && secp.sign_schnorr_with_aux_rand(&MESSAGE, &keypair, &AUX_RAND) == expected
+        && secp.verify_schnorr(&expected, &MESSAGE, &pk).is_ok()
+        && secp.verify_schnorr(&expected, &other_msg, &pk).is_err()
+}
+
+/// ECDH: SHA256("alice") and SHA256("bob") as secret keys, hashed with the default hash function.
+fn kat_ecdh() -> bool {
+    const SECRET_KEY: [u8; 32] = [
+        0x2b, 0xd8, 0x06, 0xc9, 0x7f, 0x0e, 0x00, 0xaf, 0x1a, 0x1f, 0xc3, 0x32, 0x8f, 0xa7, 0x63,
+        0xa9, 0x26, 0x97, 0x23, 0xc8, 0xdb, 0x8f, 0xac, 0x4f, 0x93, 0xaf, 0x71, 0xdb, 0x18, 0x6d,
+        0x6e, 0x90,
+    ];
+    const PEER_PUBLIC_KEY: [u8; 33] = [
+        0x02, 0x4e, 0xdf, 0xcf, 0x9d, 0xfe, 0x6c, 0x0b, 0x5c, 0x83, 0xd1, 0xab, 0x3f, 0x78, 0xd1,
+        0xb3, 0x9a, 0x46, 0xeb, 0xac, 0x67, 0x98, 0xe0, 0x8e, 0x19, 0x76, 0x1f, 0x5e, 0xd8, 0x9e,
+        0xc8, 0x3c, 0x10,
+    ];
+    const SHARED_SECRET: [u8; 32] = [
+        0x4e, 0x06, 0xde, 0x25, 0x20, 0xd1, 0xfe, 0x90, 0x9b, 0xcf, 0x24, 0x4b, 0x0a, 0x0d, 0xe5,
+        0x7c, 0x92, 0xbc, 0x6e, 0x21, 0xe2, 0x8c, 0x2c, 0xdb, 0x10, 0x8d, 0x98, 0x0a, 0xd7, 0xd7,
+        0x09, 0xb6,
+    ];
+
+    let sk = match SecretKey::from_byte_array(&SECRET_KEY) {
+        Ok(sk) => sk,
+        Err(_) => return false,
+    };
+    let pk = match PublicKey::from_byte_array_compressed(&PEER_PUBLIC_KEY) {
+        Ok(pk) => pk,
+        Err(_) => return false,
+    };
+    ecdh::SharedSecret::new(&pk, &sk).secret_bytes() == SHARED_SECRET
+}
+
+/// ElligatorSwift: the first BIP-324 decoding and x-only ECDH test vectors.
+fn kat_ellswift() -> bool {
+    const DECODE_ENCODING: [u8; 64] = [0; 64];
+    const DECODE_X: [u8; 32] = [
+        0xed, 0xd1, 0xfd, 0x3e, 0x32, 0x7c, 0xe9, 0x0c, 0xc7, 0xa3, 0x54, 0x26, 0x14, 0x28, 0x9a,
+        0xee, 0x96, 0x82, 0x00, 0x3e, 0x9c, 0xf7, 0xdc, 0xc9, 0xcf, 0x2c, 0xa9, 0x74, 0x3b, 0xe5,
+        0xaa, 0x0c,
+    ];
+
+    const SECRET_KEY: [u8; 32] = [
+        0x61, 0x06, 0x2e, 0xa5, 0x07, 0x1d, 0x80, 0x0b, 0xbf, 0xd5, 0x9e, 0x2e, 0x8b, 0x53, 0xd4,
+        0x7d, 0x19, 0x4b, 0x09, 0x5a, 0xe5, 0xa4, 0xdf, 0x04, 0x93, 0x6b, 0x49, 0x77, 0x2e, 0xf0,
+        0xd4, 0xd7,
+    ];
+    const ENCODING_OURS: [u8; 64] = [
+        0xec, 0x0a, 0xdf, 0xf2, 0x57, 0xbb, 0xfe, 0x50, 0x0c, 0x18, 0x8c, 0x80, 0xb4, 0xfd, 0xd6,
+        0x40, 0xf6, 0xb4, 0x5a, 0x48, 0x2b, 0xbc, 0x15, 0xfc, 0x7c, 0xef, 0x59, 0x31, 0xde, 0xff,
+        0x0a, 0xa1, 0x86, 0xf6, 0xeb, 0x9b, 0xba, 0x7b, 0x85, 0xdc, 0x4d, 0xcc, 0x28, 0xb2, 0x87,
+        0x22, 0xde, 0x1e, 0x3d, 0x91, 0x08, 0xb9, 0x85, 0xe2, 0x96, 0x70, 0x45, 0x66, 0x8f, 0x66,
+        0x09, 0x8e, 0x47, 0x5b,
+    ];
+    const ENCODING_THEIRS: [u8; 64] = [
+        0xa4, 0xa9, 0x4d, 0xfc, 0xe6, 0x9b, 0x4a, 0x2a, 0x0a, 0x09, 0x93, 0x13, 0xd1, 0x0f, 0x9f,
+        0x7e, 0x7d, 0x64, 0x9d, 0x60, 0x50, 0x1c, 0x9e, 0x1d, 0x27, 0x4c, 0x30, 0x0e, 0x0d, 0x89,
+        0xaa, 0xfa, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x8f, 0xaf, 0x88, 0xd5,
+    ];
+    const SHARED_SECRET: [u8; 32] = [
+        0xc6, 0x99, 0x2a, 0x11, 0x7f, 0x5e, 0xdb, 0xea, 0x70, 0xc3, 0xf5, 0x11, 0xd3, 0x2d, 0x26,
+        0xb9, 0x79, 0x8b, 0xe4, 0xb8, 0x1a, 0x62, 0xea, 0xee, 0x1a, 0x5a, 0xca, 0xa8, 0x45, 0x9a,
+        0x35, 0x92,
+    ];
+
+    let decoded = PublicKey::from_ellswift(ElligatorSwift::from_array(DECODE_ENCODING));
+    if decoded.x_only_public_key().0.serialize() != DECODE_X {
+        return false;
+    }
+
+    let sk = match SecretKey::from_byte_array(&SECRET_KEY) {
+        Ok(sk) => sk,
+        Err(_) => return false,
+    };
+    let shared_secret = ElligatorSwift::shared_secret(
+        ElligatorSwift::from_array(ENCODING_OURS),
+        ElligatorSwift::from_array(ENCODING_THEIRS),
+        sk,
+        Party::Initiator,
+        None,
+    );
+    shared_secret == ElligatorSwiftSharedSecret::from_secret_bytes(SHARED_SECRET)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use super::*;
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))] // fuzz-crypto does not produce the expected answers
+    #[cfg(feature = "alloc")]
+    fn selftest_passes() {
+        let report = selftest();
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.failures().count(), 0);
+        for test in SelfTest::ALL {
+            assert!(report.test_passed(test));
+        }
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    #[cfg(feature = "alloc")]
+    fn selftest_preallocated() {
+        use crate::ffi::types::AlignedType;
+
+        let mut buf = vec![AlignedType::zeroed(); Secp256k1::preallocate_size()];
+        let secp = Secp256k1::preallocated_new(&mut buf).unwrap();
+        assert!(secp.selftest().passed());
+    }
+
+    #[test]
+    fn report_display() {
+        let tables = if cfg!(feature = "lowmemory") { "low-memory" } else { "default" };
+
+        let report = SelfTestReport { failed: 0 };
+        assert_eq!(report.to_string(), format!("self test ({} tables): passed", tables));
+
+        let report = SelfTestReport { failed: 0b1010 };
+        assert!(!report.passed());
+        assert!(report.test_passed(SelfTest::Ecdsa));
+        assert!(!report.test_passed(SelfTest::Schnorr));
+        assert_eq!(
+            report.to_string(),
+            format!("self test ({} tables): failed: Schnorr, ElligatorSwift", tables)
+        );
+    }
+}
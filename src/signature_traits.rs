@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Implements the RustCrypto [`signature`] traits.
+//!
+//! ECDSA keys sign and verify messages hashed with SHA-256, as `ES256K` does, or digests of any
+//! 32-byte hash function. Schnorr keypairs sign and verify messages as specified by BIP 340.
+//! Signing and verifying use the global [`SECP256K1`] context.
+//!
+
+use signature::digest::consts::U32;
+use signature::digest::Digest;
+use signature::{DigestSigner, DigestVerifier, SignatureEncoding, Signer, Verifier};
+
+use crate::{
+    constants, ecdsa, schnorr, Error, Keypair, Message, PublicKey, SecretKey, XOnlyPublicKey,
+    SECP256K1,
+};
+
+/// Parses a signature in compact format.
+impl TryFrom<&[u8]> for ecdsa::Signature {
+    type Error = Error;
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> { ecdsa::Signature::from_compact(bytes) }
+}
+
+impl From<ecdsa::Signature> for [u8; constants::COMPACT_SIGNATURE_SIZE] {
+    fn from(sig: ecdsa::Signature) -> Self { sig.serialize_compact() }
+}
+
+/// Encodes signatures in compact format.
+impl SignatureEncoding for ecdsa::Signature {
+    type Repr = [u8; constants::COMPACT_SIGNATURE_SIZE];
+}
+
+impl TryFrom<&[u8]> for schnorr::Signature {
+    type Error = Error;
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = bytes.try_into().map_err(|_| Error::InvalidSignature)?;
+        Ok(schnorr::Signature::from_byte_array(bytes))
+    }
+}
+
+impl From<schnorr::Signature> for [u8; constants::SCHNORR_SIGNATURE_SIZE] {
+    fn from(sig: schnorr::Signature) -> Self { sig.to_byte_array() }
+}
+
+impl SignatureEncoding for schnorr::Signature {
+    type Repr = [u8; constants::SCHNORR_SIGNATURE_SIZE];
+}
+
+/// Returns the message to sign for the digest.
+fn digest_message<D: Digest<OutputSize = U32>>(digest: D) -> Message {
+    Message::from_digest(digest.finalize().into())
+}
+
+/// Returns the message to sign for `msg` hashed with SHA-256.
+#[cfg(feature = "hashes")]
+fn sha256_message(msg: &[u8]) -> Message {
+    use hashes::{sha256, Hash as _};
+
+    Message::from_digest(sha256::Hash::hash(msg).to_byte_array())
+}
+
+impl signature::Keypair for SecretKey {
+    type VerifyingKey = PublicKey;
+    fn verifying_key(&self) -> PublicKey { self.public_key(SECP256K1) }
+}
+
+/// Signs the SHA-256 hash of the message.
+#[cfg(feature = "hashes")]
+impl Signer<ecdsa::Signature> for SecretKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<ecdsa::Signature, signature::Error> {
+        Ok(SECP256K1.sign_ecdsa(sha256_message(msg), self))
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> DigestSigner<D, ecdsa::Signature> for SecretKey {
+    fn try_sign_digest(&self, digest: D) -> Result<ecdsa::Signature, signature::Error> {
+        Ok(SECP256K1.sign_ecdsa(digest_message(digest), self))
+    }
+}
+
+/// Verifies a signature of the SHA-256 hash of the message.
+#[cfg(feature = "hashes")]
+impl Verifier<ecdsa::Signature> for PublicKey {
+    fn verify(&self, msg: &[u8], sig: &ecdsa::Signature) -> Result<(), signature::Error> {
+        SECP256K1.verify_ecdsa(sha256_message(msg), sig, self).map_err(|_| signature::Error::new())
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> DigestVerifier<D, ecdsa::Signature> for PublicKey {
+    fn verify_digest(&self, digest: D, sig: &ecdsa::Signature) -> Result<(), signature::Error> {
+        SECP256K1
+            .verify_ecdsa(digest_message(digest), sig, self)
+            .map_err(|_| signature::Error::new())
+    }
+}
+
+/// Signs the message without auxiliary random data.
+impl Signer<schnorr::Signature> for Keypair {
+    fn try_sign(&self, msg: &[u8]) -> Result<schnorr::Signature, signature::Error> {
+        Ok(SECP256K1.sign_schnorr_no_aux_rand(msg, self))
+    }
+}
+
+/// Signs the message with auxiliary random data drawn from the random number generator.
+#[cfg(feature = "rand")]
+impl signature::RandomizedSigner<schnorr::Signature> for Keypair {
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        msg: &[u8],
+    ) -> Result<schnorr::Signature, signature::Error> {
+        Ok(SECP256K1.sign_schnorr_with_rng(msg, self, rng))
+    }
+}
+
+impl Verifier<schnorr::Signature> for XOnlyPublicKey {
+    fn verify(&self, msg: &[u8], sig: &schnorr::Signature) -> Result<(), signature::Error> {
+        SECP256K1.verify_schnorr(sig, msg, self).map_err(|_| signature::Error::new())
+    }
+}
+
+impl signature::Keypair for Keypair {
+    type VerifyingKey = XOnlyPublicKey;
+    fn verifying_key(&self) -> XOnlyPublicKey { self.x_only_public_key().0 }
+}
+
+#[cfg(test)]
+#[cfg(feature = "hashes")]
+mod tests {
+    use hashes::{sha256, Hash as _, HashEngine as _};
+    use signature::digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+    use signature::Keypair as _;
+
+    use super::*;
+
+    /// A SHA-256 `Digest` backed by `bitcoin_hashes`.
+    #[derive(Clone, Default)]
+    struct Sha256(sha256::HashEngine);
+
+    impl HashMarker for Sha256 {}
+
+    impl OutputSizeUser for Sha256 {
+        type OutputSize = U32;
+    }
+
+    impl Update for Sha256 {
+        fn update(&mut self, data: &[u8]) { self.0.input(data) }
+    }
+
+    impl FixedOutput for Sha256 {
+        fn finalize_into(self, out: &mut Output<Self>) {
+            out.copy_from_slice(sha256::Hash::from_engine(self.0).as_byte_array())
+        }
+    }
+
+    #[test]
+    fn ecdsa() {
+        let sk = SecretKey::from_byte_array(&[0xcd; 32]).unwrap();
+        let pk = sk.verifying_key();
+        assert_eq!(pk, sk.public_key(SECP256K1));
+
+        let sig: ecdsa::Signature = sk.sign(b"hello");
+        let msg = Message::from_digest(sha256::Hash::hash(b"hello").to_byte_array());
+        assert_eq!(sig, SECP256K1.sign_ecdsa(msg, &sk));
+        assert_eq!(sig, sk.sign_digest(Sha256::new_with_prefix(b"hello")));
+        assert!(Verifier::verify(&pk, b"hello", &sig).is_ok());
+        assert!(pk.verify_digest(Sha256::new_with_prefix(b"hello"), &sig).is_ok());
+        assert!(Verifier::verify(&pk, b"hello!", &sig).is_err());
+        assert!(pk.verify_digest(Sha256::new_with_prefix(b"hello!"), &sig).is_err());
+
+        let bytes = sig.to_bytes();
+        assert_eq!(bytes, sig.serialize_compact());
+        assert_eq!(ecdsa::Signature::try_from(&bytes[..]), Ok(sig));
+        assert!(ecdsa::Signature::try_from(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    #[cfg(not(secp256k1_fuzz))]
+    fn schnorr() {
+        let keypair = Keypair::from_seckey_slice(SECP256K1, &[0xcd; 32]).unwrap();
+        let pk = keypair.verifying_key();
+        assert_eq!(pk, keypair.x_only_public_key().0);
+
+        let sig: schnorr::Signature = keypair.sign(b"hello");
+        assert_eq!(sig, SECP256K1.sign_schnorr_no_aux_rand(b"hello", &keypair));
+        assert!(Verifier::verify(&pk, b"hello", &sig).is_ok());
+        assert!(Verifier::verify(&pk, b"hello!", &sig).is_err());
+
+        let bytes = sig.to_bytes();
+        assert_eq!(bytes, sig.to_byte_array());
+        assert_eq!(schnorr::Signature::try_from(&bytes[..]), Ok(sig));
+        assert!(schnorr::Signature::try_from(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "rand", feature = "std", not(secp256k1_fuzz)))]
+    fn schnorr_with_rng() {
+        use signature::RandomizedSigner as _;
+
+        let keypair = Keypair::from_seckey_slice(SECP256K1, &[0xcd; 32]).unwrap();
+
+        let sig: schnorr::Signature = keypair.sign_with_rng(&mut rand::thread_rng(), b"hello");
+        assert!(Verifier::verify(&keypair.verifying_key(), b"hello", &sig).is_ok());
+    }
+}
@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Provides the Wallet Import Format (WIF) encoding of secret keys used by Bitcoin wallets.
+//!
+//! A WIF string is the base58check encoding of a network prefix byte, the 32-byte secret key and,
+//! if the corresponding public key is used in compressed form, a trailing `0x01` byte.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use secp256k1::wif::Network;
+//! use secp256k1::SecretKey;
+//!
+//! let wif = "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617";
+//! let decoded = SecretKey::from_wif(wif).expect("valid WIF");
+//! assert_eq!(decoded.network(), Network::Mainnet);
+//! assert!(decoded.is_compressed());
+//! assert_eq!(decoded.secret_key().to_wif(Network::Mainnet, true), wif);
+//! # }
+//! ```
+//!
+
+use core::fmt;
+
+use hashes::{sha256d, Hash};
+
+#[cfg(feature = "alloc")]
+use crate::alloc::string::String;
+use crate::{constants, SecretKey};
+
+/// The base58 alphabet used by Bitcoin.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The length of a decoded WIF key: prefix, secret key, compression flag and checksum.
+const MAX_LEN: usize = 1 + constants::SECRET_KEY_SIZE + 1 + 4;
+
+/// The network a WIF key is intended for, determined by its prefix byte.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Network {
+    /// Bitcoin mainnet, prefix `0x80`.
+    Mainnet,
+    /// Bitcoin testnet, signet and regtest, prefix `0xef`.
+    Testnet,
+}
+
+impl Network {
+    /// Returns the WIF prefix byte of the network.
+    pub fn prefix(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x80,
+            Network::Testnet => 0xef,
+        }
+    }
+
+    /// Returns the network with WIF prefix byte `prefix`, if any.
+    pub fn from_prefix(prefix: u8) -> Option<Network> {
+        match prefix {
+            0x80 => Some(Network::Mainnet),
+            0xef => Some(Network::Testnet),
+            _ => None,
+        }
+    }
+}
+
+/// A secret key decoded from WIF, with its network and compression flag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Wif {
+    secret_key: SecretKey,
+    network: Network,
+    compressed: bool,
+}
+
+impl Wif {
+    /// Returns the secret key.
+    #[inline]
+    pub fn secret_key(&self) -> SecretKey { self.secret_key }
+
+    /// Returns the network the key is intended for.
+    #[inline]
+    pub fn network(&self) -> Network { self.network }
+
+    /// Returns whether the key's public key is used in compressed form.
+    #[inline]
+    pub fn is_compressed(&self) -> bool { self.compressed }
+}
+
+/// Error decoding a WIF string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The string contains a character that is not in the base58 alphabet.
+    InvalidCharacter(char),
+    /// The decoded data has the wrong length; it must be 37 or 38 bytes.
+    InvalidLength(usize),
+    /// The checksum does not match the data.
+    InvalidChecksum,
+    /// The prefix byte does not belong to a known network.
+    UnknownPrefix(u8),
+    /// The byte following the secret key is not the compression flag `0x01`.
+    InvalidCompressionFlag(u8),
+    /// The secret key is zero or not less than the curve order.
+    InvalidSecretKey,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            InvalidCharacter(c) => write!(f, "invalid base58 character {:?}", c),
+            InvalidLength(len) => write!(f, "invalid WIF length {} (expected 37 or 38)", len),
+            InvalidChecksum => f.write_str("invalid WIF checksum"),
+            UnknownPrefix(prefix) => write!(f, "unknown WIF prefix {:#04x}", prefix),
+            InvalidCompressionFlag(flag) => write!(f, "invalid WIF compression flag {:#04x}", flag),
+            InvalidSecretKey => f.write_str("invalid secret key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Decodes a base58 string into `out`, returning the number of bytes written.
+///
+/// Fails with [`Error::InvalidLength`] if the data does not fit, reporting the length decoded so
+/// far.
+fn base58_decode(s: &str, out: &mut [u8; MAX_LEN]) -> Result<usize, Error> {
+    // Big-endian accumulator; the last `len` bytes are significant.
+    let mut buf = [0u8; MAX_LEN];
+    let mut len = 0;
+    for c in s.chars() {
+        let digit =
+            ALPHABET.iter().position(|&a| char::from(a) == c).ok_or(Error::InvalidCharacter(c))?;
+        let mut carry = digit as u32;
+        for byte in buf[MAX_LEN - len..].iter_mut().rev() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            if len == MAX_LEN {
+                return Err(Error::InvalidLength(MAX_LEN + 1));
+            }
+            len += 1;
+            buf[MAX_LEN - len] = carry as u8;
+            carry >>= 8;
+        }
+    }
+    // Each leading '1' encodes a leading zero byte.
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    let total = zeros + len;
+    if total > MAX_LEN {
+        return Err(Error::InvalidLength(total));
+    }
+    out[..zeros].fill(0);
+    out[zeros..total].copy_from_slice(&buf[MAX_LEN - len..]);
+    Ok(total)
+}
+
+/// Encodes `data` as base58.
+#[cfg(feature = "alloc")]
+fn base58_encode(data: &[u8]) -> String {
+    // log(256) / log(58) < 1.37, so each byte needs at most 1.37 digits.
+    let mut digits = [0u8; MAX_LEN * 137 / 100 + 1];
+    let mut len = 0;
+    for &byte in data {
+        let mut carry = u32::from(byte);
+        for digit in digits[..len].iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits[len] = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+    }
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    let mut out = String::with_capacity(zeros + len);
+    out.extend(core::iter::repeat('1').take(zeros));
+    out.extend(digits[..len].iter().rev().map(|&d| char::from(ALPHABET[usize::from(d)])));
+    out
+}
+
+/// Returns the base58check checksum of `data`.
+fn checksum(data: &[u8]) -> [u8; 4] {
+    let hash = sha256d::Hash::hash(data).to_byte_array();
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+impl SecretKey {
+    /// Encodes the secret key in Wallet Import Format.
+    ///
+    /// Set `compressed` if the key's public key is used in compressed form, as is the case for
+    /// all modern wallets.
+    #[cfg(feature = "alloc")]
+    pub fn to_wif(&self, network: Network, compressed: bool) -> String {
+        let mut data = [0u8; MAX_LEN];
+        data[0] = network.prefix();
+        data[1..33].copy_from_slice(&self.secret_bytes());
+        let len = if compressed {
+            data[33] = 0x01;
+            34
+        } else {
+            33
+        };
+        let sum = checksum(&data[..len]);
+        data[len..len + 4].copy_from_slice(&sum);
+        base58_encode(&data[..len + 4])
+    }
+
+    /// Decodes a secret key in Wallet Import Format, along with its network and compression flag.
+    pub fn from_wif(wif: &str) -> Result<Wif, Error> {
+        let mut data = [0u8; MAX_LEN];
+        let len = base58_decode(wif, &mut data)?;
+        if len != MAX_LEN && len != MAX_LEN - 1 {
+            return Err(Error::InvalidLength(len));
+        }
+        let (payload, sum) = data[..len].split_at(len - 4);
+        if checksum(payload) != sum {
+            return Err(Error::InvalidChecksum);
+        }
+        let network = Network::from_prefix(payload[0]).ok_or(Error::UnknownPrefix(payload[0]))?;
+        let compressed = match payload[33..] {
+            [] => false,
+            [0x01] => true,
+            [flag] => return Err(Error::InvalidCompressionFlag(flag)),
+            _ => unreachable!("length checked above"),
+        };
+        let secret_key =
+            SecretKey::from_slice(&payload[1..33]).map_err(|_| Error::InvalidSecretKey)?;
+        Ok(Wif { secret_key, network, compressed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+
+    fn secret_key() -> SecretKey {
+        SecretKey::from_byte_array(&hex!(
+            "0c28fca386c7a227600b2fe50b7cae11ec86d3bf1fbe471be89827e19d72aa1d"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn vectors() {
+        let vectors = [
+            ("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ", Network::Mainnet, false),
+            ("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617", Network::Mainnet, true),
+            ("91gGn1HgSap6CbU12F6z3pJri26xzp7Ay1VW6NHCoEayNXwRpu2", Network::Testnet, false),
+            ("cMzLdeGd5vEqxB8B6VFQoRopQ3sLAAvEzDAoQgvX54xwofSWj1fx", Network::Testnet, true),
+        ];
+        let sk = secret_key();
+        for (wif, network, compressed) in vectors {
+            assert_eq!(SecretKey::from_wif(wif), Ok(Wif { secret_key: sk, network, compressed }));
+            #[cfg(feature = "alloc")]
+            assert_eq!(sk.to_wif(network, compressed), wif);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let cases = [
+            ("", Error::InvalidLength(0)),
+            // Last character changed.
+            ("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98618", Error::InvalidChecksum),
+            ("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP9861O", Error::InvalidCharacter('O')),
+            ("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP9861l", Error::InvalidCharacter('l')),
+            ("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP986 7", Error::InvalidCharacter(' ')),
+            // A 31-byte key, a prefix of 0x81 and a compression flag of 0x02.
+            ("yPoVP5njSzmEVK4VJGRWWAwqnwCyLPRcMm5XyrKgY1DE64xhu", Error::InvalidLength(36)),
+            ("L6Cyjtdfq1TimFfgG9qBEHQF2FmaL96H5ceTrip74S4eqFapbcDJ", Error::UnknownPrefix(0x81)),
+            (
+                "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvWxyf5d",
+                Error::InvalidCompressionFlag(2),
+            ),
+            // Zero and out of range secret keys.
+            ("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73Nd2Mcv1", Error::InvalidSecretKey),
+            ("L5oLkpV3aqBjhki6LmvChTCq73v9gyymzzMpBbhDLjDpKCuAXpsi", Error::InvalidSecretKey),
+        ];
+        for (wif, error) in cases {
+            assert_eq!(SecretKey::from_wif(wif), Err(error), "{}", wif);
+        }
+
+        // Too long, both by value and by leading zeros.
+        let long = "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617KwdMAj";
+        assert!(matches!(SecretKey::from_wif(long), Err(Error::InvalidLength(_))));
+        let zeros = "1111111111111111111111111111111111111111";
+        assert_eq!(SecretKey::from_wif(zeros), Err(Error::InvalidLength(40)));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn base58_round_trip() {
+        for data in [&[][..], &[0], &[0, 0, 1], &[0xff; 38], &[0x61], &[0x62, 0x62, 0x62]] {
+            let encoded = base58_encode(data);
+            let mut decoded = [0u8; MAX_LEN];
+            let len = base58_decode(&encoded, &mut decoded).unwrap();
+            assert_eq!(&decoded[..len], data);
+        }
+        assert_eq!(base58_encode(&[0, 0, 0x61]), "112g");
+        assert_eq!(base58_encode(b"bbb"), "a3gV");
+    }
+}
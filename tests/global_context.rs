@@ -0,0 +1,34 @@
+//! Tests the initialization of the global context without `std`.
+//!
+//! These run in their own process because the global context can only be initialized once.
+
+#![cfg(all(feature = "global-context-no-std", not(feature = "std"), not(secp256k1_fuzz)))]
+
+extern crate secp256k1;
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use secp256k1::{global, PublicKey, SecretKey};
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Panics the first time it is called, like a hardware RNG that is not ready yet.
+fn flaky_rng() -> [u8; 32] {
+    if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+        panic!("rng not ready");
+    }
+    [0x42; 32]
+}
+
+#[test]
+fn panicking_entropy_hook() {
+    assert!(global::set_entropy_hook(flaky_rng));
+    let sk = SecretKey::from_byte_array(&[1; 32]).unwrap();
+
+    assert!(panic::catch_unwind(|| PublicKey::from_secret_key_global(&sk)).is_err());
+    // The next access creates the context again instead of deadlocking.
+    let pk = PublicKey::from_secret_key_global(&sk);
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    assert_eq!(PublicKey::from_secret_key_global(&sk), pk);
+}
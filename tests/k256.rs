@@ -0,0 +1,95 @@
+//! Differential tests checking that this library and the RustCrypto `k256` crate agree.
+
+#![cfg(all(feature = "k256", feature = "alloc", not(secp256k1_fuzz)))]
+
+extern crate k256;
+extern crate secp256k1;
+
+use k256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::sha2::{Digest, Sha256};
+use secp256k1::{ecdh, ecdsa, Message, PublicKey, Secp256k1, SecretKey};
+
+/// The number of random cases of each test.
+const CASES: u32 = 256;
+
+/// Returns the `i`th pseudorandom 32-byte string for `domain`.
+fn random_bytes(domain: &str, i: u32) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(domain.as_bytes());
+    hasher.update(i.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Returns the `i`th pseudorandom secret key.
+fn random_secret_key(i: u32) -> SecretKey {
+    (0..)
+        .find_map(|j| SecretKey::from_byte_array(&random_bytes("secret key", i * 16 + j)).ok())
+        .unwrap()
+}
+
+#[test]
+fn public_keys() {
+    let secp = Secp256k1::new();
+    for i in 0..CASES {
+        let sk = random_secret_key(i);
+        let pk = sk.public_key(&secp);
+        let k256_pk = k256::SecretKey::from(sk).public_key();
+
+        assert_eq!(PublicKey::from(k256_pk), pk);
+        assert_eq!(k256_pk.to_encoded_point(true).as_bytes(), pk.serialize());
+        assert_eq!(k256_pk.to_encoded_point(false).as_bytes(), pk.serialize_uncompressed());
+    }
+}
+
+#[test]
+fn ecdsa_signatures() {
+    let secp = Secp256k1::new();
+    for i in 0..CASES {
+        let sk = random_secret_key(i);
+        let pk = sk.public_key(&secp);
+        let digest = random_bytes("message", i);
+        let msg = Message::from_digest(digest);
+
+        let signing_key = k256::ecdsa::SigningKey::from(k256::NonZeroScalar::from(sk));
+        let verifying_key = k256::ecdsa::VerifyingKey::from(k256::PublicKey::from(pk));
+
+        // Both libraries use RFC 6979 nonces and low-S normalization, so they agree exactly.
+        let sig = secp.sign_ecdsa(msg, &sk);
+        let k256_sig: k256::ecdsa::Signature = signing_key.sign_prehash(&digest).unwrap();
+        assert_eq!(ecdsa::Signature::from(k256_sig), sig);
+
+        assert!(verifying_key.verify_prehash(&digest, &k256_sig).is_ok());
+        assert!(secp.verify_ecdsa(msg, &sig, &pk).is_ok());
+
+        let mut other = digest;
+        other[0] ^= 1;
+        assert!(verifying_key.verify_prehash(&other, &k256_sig).is_err());
+        assert!(secp.verify_ecdsa(Message::from_digest(other), &sig, &pk).is_err());
+
+        // Both reject signatures with a high `s`.
+        let (r, s) = k256_sig.split_scalars();
+        let high_s = k256::ecdsa::Signature::from_scalars(r, -*s).unwrap();
+        assert!(verifying_key.verify_prehash(&digest, &high_s).is_err());
+        assert!(secp.verify_ecdsa(msg, &ecdsa::Signature::from(high_s), &pk).is_err());
+    }
+}
+
+#[test]
+fn ecdh() {
+    let secp = Secp256k1::new();
+    for i in 0..CASES {
+        let sk = random_secret_key(i);
+        let pk = random_secret_key(i + CASES).public_key(&secp);
+
+        let point = k256::ProjectivePoint::from(k256::AffinePoint::from(pk))
+            * *k256::NonZeroScalar::from(sk);
+        let point = point.to_affine().to_encoded_point(false);
+        assert_eq!(point.as_bytes()[1..], ecdh::shared_secret_point(&pk, &sk));
+
+        // The default hash of libsecp256k1 is SHA-256 of the compressed point.
+        let compressed = point.compress();
+        let expected: [u8; 32] = Sha256::digest(compressed.as_bytes()).into();
+        assert_eq!(ecdh::SharedSecret::new(&pk, &sk).secret_bytes(), expected);
+    }
+}
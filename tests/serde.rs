@@ -3,10 +3,16 @@
 extern crate bincode;
 extern crate secp256k1;
 extern crate serde_cbor;
+#[cfg(feature = "recovery")]
+extern crate serde_test;
 
+#[cfg(feature = "recovery")]
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
 #[cfg(feature = "global-context")]
 use secp256k1::{Keypair, Secp256k1};
 use secp256k1::{PublicKey, SecretKey, XOnlyPublicKey};
+#[cfg(feature = "recovery")]
+use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
 
 // Arbitrary key data.
 
@@ -35,6 +41,27 @@ static XONLY_PK_BYTES: [u8; 32] = [
     0x4a, 0xc8, 0x87, 0xfe, 0x91, 0xdd, 0xd1, 0x66,
 ];
 
+#[cfg(feature = "recovery")]
+#[rustfmt::skip]
+static RECOVERABLE_SIG_BYTES: [u8; 65] = [
+    0x66, 0x73, 0xff, 0xad, 0x21, 0x47, 0x74, 0x1f,
+    0x04, 0x77, 0x2b, 0x6f, 0x92, 0x1f, 0x0b, 0xa6,
+    0xaf, 0x0c, 0x1e, 0x77, 0xfc, 0x43, 0x9e, 0x65,
+    0xc3, 0x6d, 0xed, 0xf4, 0x09, 0x2e, 0x88, 0x98,
+    0x4c, 0x1a, 0x97, 0x16, 0x52, 0xe0, 0xad, 0xa8,
+    0x80, 0x12, 0x0e, 0xf8, 0x02, 0x5e, 0x70, 0x9f,
+    0xff, 0x20, 0x80, 0xc4, 0xa3, 0x9a, 0xae, 0x06,
+    0x8d, 0x12, 0xee, 0xd0, 0x09, 0xb6, 0x8c, 0x89,
+    0x01,
+];
+
+#[cfg(feature = "recovery")]
+static RECOVERABLE_SIG_STR: &str = "\
+    6673ffad2147741f04772b6f921f0ba6af0c1e77fc439e65c36dedf4092e8898\
+    4c1a971652e0ada880120ef8025e709fff2080c4a39aae068d12eed009b68c89\
+    01\
+";
+
 fn secret_key() -> SecretKey {
     SecretKey::from_slice(&SK_BYTES).expect("failed to create sk from slice")
 }
@@ -85,3 +112,50 @@ fn cbor() {
     // It also adds a 1-byte length prefix and a byte of metadata for the whole vector.
     assert_eq!(e.len(), 54);
 }
+
+#[cfg(feature = "recovery")]
+fn recoverable_signature() -> RecoverableSignature {
+    let recid = RecoveryId::try_from(i32::from(RECOVERABLE_SIG_BYTES[64])).unwrap();
+    RecoverableSignature::from_compact(&RECOVERABLE_SIG_BYTES[..64], recid).unwrap()
+}
+
+#[test]
+#[cfg(feature = "recovery")]
+fn bincode_recoverable_signature() {
+    let sig = recoverable_signature();
+    let ser = bincode::serialize(&sig).unwrap();
+
+    // Bincode prefixes byte strings with their length as a `u64`.
+    assert_eq!(ser[..8], 65u64.to_le_bytes());
+    assert_eq!(ser[8..], RECOVERABLE_SIG_BYTES);
+    assert_eq!(bincode::deserialize::<RecoverableSignature>(&ser).unwrap(), sig);
+}
+
+#[test]
+#[cfg(feature = "recovery")]
+fn serde_recoverable_signature() {
+    let sig = recoverable_signature();
+
+    assert_tokens(&sig.compact(), &[Token::BorrowedBytes(&RECOVERABLE_SIG_BYTES)]);
+    assert_tokens(&sig.compact(), &[Token::Bytes(&RECOVERABLE_SIG_BYTES)]);
+    assert_tokens(&sig.compact(), &[Token::ByteBuf(&RECOVERABLE_SIG_BYTES)]);
+    assert_tokens(&sig.readable(), &[Token::BorrowedStr(RECOVERABLE_SIG_STR)]);
+    assert_tokens(&sig.readable(), &[Token::Str(RECOVERABLE_SIG_STR)]);
+    assert_tokens(&sig.readable(), &[Token::String(RECOVERABLE_SIG_STR)]);
+
+    assert_de_tokens_error::<serde_test::Compact<RecoverableSignature>>(
+        &[Token::Bytes(&RECOVERABLE_SIG_BYTES[..64])],
+        "malformed signature",
+    );
+}
+
+#[test]
+#[cfg(feature = "recovery")]
+fn serde_recovery_id() {
+    assert_tokens(&RecoveryId::Zero, &[Token::U8(0)]);
+    assert_tokens(&RecoveryId::Three, &[Token::U8(3)]);
+    assert_de_tokens_error::<RecoveryId>(
+        &[Token::U8(4)],
+        "invalid value: integer `4`, expected a recovery ID from 0 to 3",
+    );
+}
@@ -0,0 +1,261 @@
+//! Runs the Project Wycheproof ECDSA and ECDH test vectors for secp256k1 through this library.
+//!
+//! The vectors in `tests/data/wycheproof` are copied from the Wycheproof project, see
+//! `tests/data/wycheproof/WYCHEPROOF_COPYING` for their license. The upstream C library runs the
+//! bitcoin ECDSA vectors directly, so those check that the Rust parsing layer agrees with it. The
+//! generic ECDSA vectors also accept high-S signatures, and the ECDH vectors encode the public
+//! keys as SPKI DER, which exercises the `der` module.
+
+#![cfg(all(feature = "hashes", feature = "alloc", not(secp256k1_fuzz)))]
+
+extern crate secp256k1;
+extern crate serde_json;
+
+use secp256k1::ecdh::{shared_secret_point, SharedSecret};
+use secp256k1::ecdsa::Signature;
+use secp256k1::hashes::{sha256, Hash};
+use secp256k1::{Error, Message, PublicKey, Secp256k1, SecretKey};
+use serde_json::Value;
+
+/// The expected result of a test vector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Expected {
+    /// The signature is valid.
+    Valid,
+    /// The signature is invalid.
+    Invalid,
+    /// The signature may be accepted or rejected, depending on how strict the implementation is.
+    Acceptable,
+}
+
+/// An ECDSA verification test vector.
+struct EcdsaTest {
+    id: u64,
+    comment: String,
+    flags: Vec<String>,
+    pk: PublicKey,
+    msg: Message,
+    der: Vec<u8>,
+    expected: Expected,
+}
+
+impl EcdsaTest {
+    /// Returns true if this test vector has `flag`.
+    fn has_flag(&self, flag: &str) -> bool { self.flags.iter().any(|f| f == flag) }
+}
+
+/// Decodes a hex string.
+fn from_hex(hex: &str) -> Vec<u8> {
+    assert_eq!(hex.len() % 2, 0, "odd length hex string {}", hex);
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+}
+
+/// Parses the SHA-256 ECDSA test vectors of a Wycheproof JSON file.
+fn ecdsa_tests(json: &str) -> Vec<EcdsaTest> {
+    let file: Value = serde_json::from_str(json).unwrap();
+    assert_eq!(file["algorithm"], "ECDSA");
+
+    let mut tests = vec![];
+    for group in file["testGroups"].as_array().unwrap() {
+        assert_eq!(group["publicKey"]["curve"], "secp256k1");
+        assert_eq!(group["sha"], "SHA-256");
+        let pk = from_hex(group["publicKey"]["uncompressed"].as_str().unwrap());
+        let pk = PublicKey::from_slice(&pk).unwrap();
+
+        for test in group["tests"].as_array().unwrap() {
+            let msg = from_hex(test["msg"].as_str().unwrap());
+            let expected = match test["result"].as_str().unwrap() {
+                "valid" => Expected::Valid,
+                "invalid" => Expected::Invalid,
+                "acceptable" => Expected::Acceptable,
+                result => panic!("unknown result {}", result),
+            };
+            tests.push(EcdsaTest {
+                id: test["tcId"].as_u64().unwrap(),
+                comment: test["comment"].as_str().unwrap().to_owned(),
+                flags: test["flags"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|flag| flag.as_str().unwrap().to_owned())
+                    .collect(),
+                pk,
+                msg: Message::from_digest(sha256::Hash::hash(&msg).to_byte_array()),
+                der: from_hex(test["sig"].as_str().unwrap()),
+                expected,
+            });
+        }
+    }
+    assert_eq!(tests.len() as u64, file["numberOfTests"].as_u64().unwrap());
+    tests
+}
+
+/// Checks the outcome of `result` for a test vector expected to be `expected`.
+///
+/// This library is strict, so it rejects every vector that may be accepted or rejected.
+fn check_expected<E: std::fmt::Debug>(result: Result<(), E>, expected: Expected, context: &str) {
+    match expected {
+        Expected::Valid => assert!(result.is_ok(), "{}: {:?}", context, result),
+        Expected::Invalid | Expected::Acceptable => assert!(result.is_err(), "{}", context),
+    }
+}
+
+/// Checks that lax parsing accepts everything strict parsing does.
+///
+/// Both parsers replace integers that overflow the curve order with zero, but the lax one zeroes
+/// the whole signature, so they only agree on signatures that round trip. The others never verify.
+fn check_lax(
+    test: &EcdsaTest,
+    sig: Result<Signature, Error>,
+    lax: Result<Signature, Error>,
+    result: Result<(), Error>,
+    context: &str,
+) {
+    if let Ok(sig) = sig {
+        if sig.serialize_der().as_ref() == &test.der[..] {
+            assert_eq!(lax, Ok(sig), "{}", context);
+        } else {
+            assert!(lax.is_ok(), "{}", context);
+            assert!(result.is_err(), "{}", context);
+        }
+    }
+}
+
+#[test]
+fn ecdsa_bitcoin() {
+    let secp = Secp256k1::verification_only();
+    let tests =
+        ecdsa_tests(include_str!("data/wycheproof/ecdsa_secp256k1_sha256_bitcoin_test.json"));
+
+    for test in &tests {
+        let context = format!("tcId {} ({}, {:?})", test.id, test.comment, test.flags);
+        let sig = Signature::from_der(&test.der);
+        let lax = Signature::from_der_lax(&test.der);
+
+        let result = sig.and_then(|sig| secp.verify_ecdsa(test.msg, &sig, &test.pk));
+        check_expected(result, test.expected, &context);
+        check_lax(test, sig, lax, result, &context);
+
+        // Verification only accepts low-S signatures, so normalizing them does nothing.
+        if let Ok(sig) = sig {
+            let mut normalized = sig;
+            normalized.normalize_s();
+            if result.is_ok() {
+                assert_eq!(normalized, sig, "{}", context);
+            }
+            if test.has_flag("SignatureMalleabilityBitcoin") {
+                assert_ne!(normalized, sig, "{}", context);
+                assert_eq!(
+                    secp.verify_ecdsa(test.msg, &normalized, &test.pk),
+                    Ok(()),
+                    "{}",
+                    context
+                );
+            }
+        }
+
+        // BER encodings of valid signatures are rejected by strict parsing only.
+        if test.has_flag("BerEncodedSignature") {
+            assert!(sig.is_err(), "{}", context);
+            let lax = lax.unwrap_or_else(|e| panic!("{}: {}", context, e));
+            assert_eq!(secp.verify_ecdsa(test.msg, &lax, &test.pk), Ok(()), "{}", context);
+        }
+    }
+}
+
+#[test]
+fn ecdsa_generic() {
+    let secp = Secp256k1::verification_only();
+    let tests = ecdsa_tests(include_str!("data/wycheproof/ecdsa_secp256k1_sha256_test.json"));
+
+    for test in &tests {
+        let context = format!("tcId {} ({}, {:?})", test.id, test.comment, test.flags);
+        let sig = Signature::from_der(&test.der);
+        let lax = Signature::from_der_lax(&test.der);
+
+        // Plain ECDSA accepts high-S signatures, which verify once they are normalized.
+        let normalize = |mut sig: Signature| {
+            sig.normalize_s();
+            sig
+        };
+        let result = sig.map(normalize).and_then(|sig| secp.verify_ecdsa(test.msg, &sig, &test.pk));
+        check_expected(result, test.expected, &context);
+        check_lax(test, sig, lax, result, &context);
+
+        // Verification without normalization accepts exactly the valid low-S signatures.
+        if let (Ok(sig), Ok(())) = (sig, result) {
+            let raw = secp.verify_ecdsa(test.msg, &sig, &test.pk);
+            assert_eq!(raw.is_ok(), normalize(sig) == sig, "{}", context);
+        }
+
+        // BER encodings of valid signatures are rejected by strict parsing only.
+        if test.has_flag("BerEncodedSignature") {
+            assert!(sig.is_err(), "{}", context);
+            let lax = lax.map(normalize).unwrap_or_else(|e| panic!("{}: {}", context, e));
+            assert_eq!(secp.verify_ecdsa(test.msg, &lax, &test.pk), Ok(()), "{}", context);
+        }
+    }
+}
+
+#[test]
+fn ecdh() {
+    let file: Value =
+        serde_json::from_str(include_str!("data/wycheproof/ecdh_secp256k1_test.json")).unwrap();
+    assert_eq!(file["algorithm"], "ECDH");
+
+    let mut count = 0;
+    for group in file["testGroups"].as_array().unwrap() {
+        assert_eq!(group["curve"], "secp256k1");
+        assert_eq!(group["encoding"], "asn");
+
+        for test in group["tests"].as_array().unwrap() {
+            count += 1;
+            let context = format!("tcId {} ({}, {})", test["tcId"], test["comment"], test["flags"]);
+            let expected = match test["result"].as_str().unwrap() {
+                "valid" => Expected::Valid,
+                "invalid" => Expected::Invalid,
+                "acceptable" => Expected::Acceptable,
+                result => panic!("unknown result {}", result),
+            };
+
+            // The private keys are big-endian integers, with or without leading zeros.
+            let private = from_hex(test["private"].as_str().unwrap());
+            let private = &private[private.iter().take_while(|&&b| b == 0).count()..];
+            let mut sk = [0; 32];
+            sk[32 - private.len()..].copy_from_slice(private);
+            let sk = SecretKey::from_byte_array(&sk).unwrap();
+
+            let pk = PublicKey::from_spki_der(&from_hex(test["public"].as_str().unwrap()));
+            match (expected, pk) {
+                (Expected::Invalid, pk) => {
+                    assert!(pk.is_err(), "{}", context);
+                    continue;
+                }
+                // Compressed points are the only variation of the encoding this library accepts.
+                (Expected::Acceptable, Ok(_)) => assert!(
+                    test["flags"].as_array().unwrap().iter().any(|f| f == "CompressedPublic"),
+                    "{}",
+                    context
+                ),
+                (Expected::Acceptable, Err(_)) => continue,
+                (Expected::Valid, Err(e)) => panic!("{}: {}", context, e),
+                (Expected::Valid, Ok(_)) => {}
+            }
+            let pk = pk.unwrap();
+
+            let shared = from_hex(test["shared"].as_str().unwrap());
+            let point = shared_secret_point(&pk, &sk);
+            assert_eq!(point[..32], shared[..], "{}", context);
+
+            let mut compressed = [0x02 | (point[63] & 1); 33];
+            compressed[1..].copy_from_slice(&point[..32]);
+            assert_eq!(
+                SharedSecret::new(&pk, &sk).secret_bytes(),
+                sha256::Hash::hash(&compressed).to_byte_array(),
+                "{}",
+                context
+            );
+        }
+    }
+    assert_eq!(count, file["numberOfTests"].as_u64().unwrap());
+}